@@ -6,6 +6,7 @@
 // Pattern: chama-optics js/heif_helper.js
 
 let tesseractWorker = null;
+let osdWorker = null;
 
 // Initialize tesseract worker with Korean + English
 async function initWorker() {
@@ -18,10 +19,27 @@ async function initWorker() {
     });
 }
 
+// Load the recognition worker and its language data ahead of the first OCR run
+export async function ocr_init() {
+    await initWorker();
+}
+
+// Orientation/script detection needs the legacy engine and 'osd' traineddata,
+// so it runs on its own worker, created only when auto-rotate is first used
+async function initOsdWorker() {
+    if (osdWorker) return;
+    osdWorker = await Tesseract.createWorker('osd', 0, {
+        legacyCore: true,
+        legacyLang: true,
+    });
+}
+
 // Preprocess image for better OCR accuracy:
 // 1. Convert to grayscale
 // 2. Scale up small images (Tesseract works best at 300+ DPI)
 // 3. Otsu binarization (automatic threshold, proven with Tesseract)
+// Resolves to { bytes, scale, width, height } where width/height are the
+// original image size and scale maps OCR coordinates back to it
 async function preprocessForOcr(imageBytes) {
     return new Promise((resolve) => {
         const blob = new Blob([imageBytes]);
@@ -106,20 +124,27 @@ async function preprocessForOcr(imageBytes) {
 
                 canvas.toBlob(
                     (blob) => {
-                        blob.arrayBuffer().then((buf) => resolve(new Uint8Array(buf)));
+                        blob.arrayBuffer().then((buf) =>
+                            resolve({
+                                bytes: new Uint8Array(buf),
+                                scale,
+                                width: img.width,
+                                height: img.height,
+                            })
+                        );
                     },
                     'image/png'
                 );
             } catch (e) {
                 console.warn('Image preprocessing failed, using original:', e);
-                resolve(imageBytes);
+                resolve({ bytes: imageBytes, scale: 1, width: img.width, height: img.height });
             }
             URL.revokeObjectURL(url);
         };
 
         img.onerror = () => {
             URL.revokeObjectURL(url);
-            resolve(imageBytes);
+            resolve({ bytes: imageBytes, scale: 1, width: 0, height: 0 });
         };
 
         img.src = url;
@@ -127,25 +152,55 @@ async function preprocessForOcr(imageBytes) {
 }
 
 // Called from Rust: perform OCR on image bytes (Uint8Array)
-// Returns recognized text string
-export async function ocr_recognize(imageBytes) {
+// Returns { text, width, height, words: [{ text, x0, y0, x1, y1 }], lines: [...] }
+// with word/line boxes in original image pixels (preprocessing upscale undone)
+export async function ocr_recognize_words(imageBytes) {
     await initWorker();
     const processed = await preprocessForOcr(imageBytes);
     console.log(
-        `OCR: preprocessed ${imageBytes.length} -> ${processed.length} bytes`
+        `OCR: preprocessed ${imageBytes.length} -> ${processed.bytes.length} bytes`
     );
-    const result = await tesseractWorker.recognize(processed);
+    const result = await tesseractWorker.recognize(processed.bytes);
     console.log('OCR result:', result.data.text.substring(0, 200));
-    return result.data.text;
+    const s = processed.scale || 1;
+    const toBox = (item) => ({
+        text: item.text.trim(),
+        x0: item.bbox.x0 / s,
+        y0: item.bbox.y0 / s,
+        x1: item.bbox.x1 / s,
+        y1: item.bbox.y1 / s,
+    });
+    const words = (result.data.words || []).map(toBox);
+    const lines = (result.data.lines || []).map(toBox);
+    console.log(`OCR: ${words.length} words, ${lines.length} lines with bounding boxes`);
+    return {
+        text: result.data.text,
+        width: processed.width,
+        height: processed.height,
+        words,
+        lines,
+    };
+}
+
+// Called from Rust: detect page orientation (OSD)
+// Returns clockwise rotation in degrees (0, 90, 180, 270) that makes the text upright
+export async function ocr_detect_orientation(imageBytes) {
+    await initOsdWorker();
+    const result = await osdWorker.detect(imageBytes);
+    console.log(
+        `OCR: orientation ${result.data.orientation_degrees} deg (confidence ${result.data.orientation_confidence})`
+    );
+    return result.data.orientation_degrees || 0;
 }
 
 // Called from Rust: open file picker and return array of {name, bytes}
-export function open_file_picker(queue_callback) {
+// `accept` narrows the picker to the given types (images when omitted)
+export function open_file_picker(accept) {
     return new Promise((resolve) => {
         const input = document.createElement('input');
         input.type = 'file';
         input.multiple = true;
-        input.accept = 'image/jpeg,image/png,image/jpg';
+        input.accept = accept || 'image/jpeg,image/png,image/jpg';
         input.onchange = async () => {
             const results = [];
             for (const file of input.files) {
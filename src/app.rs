@@ -7,14 +7,23 @@
 //! Main eframe::App implementation
 //! Pattern: chama-optics src/app.rs (Arc<Mutex<Vec>> queue + spawn_local + polling)
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 
 use chrono::NaiveDateTime;
 use eframe::egui;
 
+use crate::csv_schema::CsvSchema;
 use crate::expense;
-use crate::model::{AppState, CardTransaction, PendingImage};
+use crate::model::{
+    AppState, CardTransaction, CsvAmountFormat, DEFAULT_EXPENSE_COLOR, DigitGrouping, FailedImage,
+    MergeAmount, MergeChoices, MergeSource, OcrLayout, OptionalColumn, PendingImage,
+    UNCATEGORIZED_LABEL, amounts_far_apart, format_amount, format_amount_grouped, local_now,
+    parse_amount_input, parse_filter_date, unset_datetime,
+};
 use crate::parser;
+use crate::pdf_export::PdfLayout;
+use crate::preprocess::{self, ImageAdjust};
 use crate::table;
 
 #[cfg(target_arch = "wasm32")]
@@ -24,8 +33,78 @@ use crate::web_download;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_futures::spawn_local;
 
-/// Completed OCR result: Ok(transaction) or Err(filename, error)
-type OcrResult = Result<CardTransaction, (String, String)>;
+/// Completed OCR result: Ok(transaction) or Err(the image and why it failed)
+type OcrResult = Result<CardTransaction, FailedImage>;
+
+/// Date format of the edit panel's 날짜 field (seconds may be left off when typing)
+const EDIT_DATETIME_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
+/// Longest side of the side-panel preview texture
+const PREVIEW_MAX_SIDE: u32 = 1024;
+/// Longest side of pending-image thumbnails
+const THUMBNAIL_MAX_SIDE: u32 = 160;
+/// Thumbnails decoded per frame, so a large drop doesn't freeze the UI
+const THUMBNAILS_PER_FRAME: usize = 4;
+/// Decoded previews kept for revisiting rows without re-decoding
+const PREVIEW_CACHE_SIZE: usize = 12;
+/// OCR results kept for re-processing an image; each holds a full-size image copy
+const OCR_CACHE_SIZE: usize = 8;
+/// Matching expense types listed after the top recommendation in the edit panel
+const MAX_EXTRA_RECOMMENDATIONS: usize = 4;
+/// Loaded image bytes above which the status bar recommends working in batches
+const IMAGE_BYTES_WARNING: usize = 150 * 1024 * 1024;
+/// Loaded image bytes above which new uploads are refused; browser tabs tend
+/// to run out of memory not far beyond this
+const IMAGE_BYTES_LIMIT: usize = 300 * 1024 * 1024;
+
+// eframe storage keys for the persisted session
+const STORAGE_TRANSACTIONS: &str = "transactions";
+const STORAGE_SORT_COLUMN: &str = "sort_column";
+const STORAGE_SORT_DIRECTION: &str = "sort_direction";
+const STORAGE_EXPENSE_RULES: &str = "expense_rules";
+const STORAGE_EXPENSE_COLORS: &str = "expense_colors";
+const STORAGE_VISIBLE_COLUMNS: &str = "visible_columns";
+const STORAGE_AMOUNT_CEILING: &str = "amount_ceiling";
+const STORAGE_BUDGET_LIMIT: &str = "budget_limit";
+
+/// Export actions that go through pre-export validation
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExportKind {
+    Csv,
+    Json,
+    Html,
+    Zip,
+    /// PDF of the checked rows (all rows when multi-select is off)
+    SelectedPdf,
+}
+
+/// What a file picked for a specific row is for
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RowImageAction {
+    /// "이미지 교체": swap the row's image
+    Replace,
+    /// "첨부": add to the row's `extra_images`
+    Attach,
+}
+
+/// (rotated image, text, word boxes) keyed by `ocr_cache_key` of the upload,
+/// least recently used first
+type OcrCache = VecDeque<(u64, (Arc<[u8]>, String, OcrLayout))>;
+
+/// Thumbnail of a pending image. The downscaled upload is kept unadjusted, so a
+/// slider change only re-applies the adjustment instead of decoding again.
+struct Thumbnail {
+    source: image::DynamicImage,
+    texture: egui::TextureHandle,
+}
+
+/// Text typed into the range filter fields; parsed into `AppState::filter` on change
+#[derive(Default)]
+struct FilterInputs {
+    date_from: String,
+    date_to: String,
+    amount_min: String,
+    amount_max: String,
+}
 
 pub struct CardReceiptApp {
     state: AppState,
@@ -34,34 +113,115 @@ pub struct CardReceiptApp {
     /// File picker pushes new files here
     #[allow(clippy::type_complexity)]
     file_queue: Arc<Mutex<Vec<(String, Vec<u8>)>>>,
-    /// Number of OCR tasks currently in flight
+    /// "이미지 교체"/"첨부" pickers push (row id, action, filename, bytes) here
+    #[allow(clippy::type_complexity)]
+    row_image_queue: Arc<Mutex<Vec<(u64, RowImageAction, String, Vec<u8>)>>>,
+    /// OCR results for replaced images, keyed by row id
+    rerun_queue: Arc<Mutex<Vec<(u64, OcrResult)>>>,
+    /// Number of OCR tasks queued or in flight
     ocr_remaining: Arc<Mutex<usize>>,
+    /// Images waiting for a free OCR slot
+    ocr_queue: VecDeque<PendingImage>,
+    /// Number of OCR tasks currently running (capped by `ocr_concurrency`)
+    ocr_in_flight: Arc<Mutex<usize>>,
+    /// OCR results, so re-processing an image skips orientation detection and Tesseract
+    ocr_cache: Arc<Mutex<OcrCache>>,
     // Preview / edit state
-    preview_texture: Option<egui::TextureHandle>,
-    preview_loaded_for: Option<usize>,
+    /// Decoded preview for the selected row; Err carries the reason it could not be shown
+    preview_texture: Option<Result<egui::TextureHandle, String>>,
+    /// `CardTransaction::id` the preview and edit fields were loaded from
+    preview_loaded_for: Option<u64>,
+    /// Recently shown previews keyed by image content hash, least recently used first
+    #[allow(clippy::type_complexity)]
+    preview_cache: VecDeque<(u64, Result<egui::TextureHandle, String>)>,
+    filter_inputs: FilterInputs,
+    /// Text of the 예산 field; parsed into `AppState::budget_limit` on change
+    budget_input: String,
+    /// Side-by-side comparison of two checked rows: (`CardTransaction::id`, decoded image)
+    /// pairs, so sorting or deleting rows while the window is open can't swap what it shows
+    #[allow(clippy::type_complexity)]
+    compare: Option<[(u64, Result<egui::TextureHandle, String>); 2]>,
+    /// Zoom shared by both images in the comparison window (1.0 = fit)
+    compare_zoom: f32,
+    /// "병합" dialog for two checked rows: (`CardTransaction::id`s in table order when
+    /// opened, field choices). Ids, since rows can be sorted or deleted while it's open
+    merge_dialog: Option<([u64; 2], MergeChoices)>,
+    /// "크게 보기" window visibility and its zoom (1.0 = fit to the window)
+    large_preview_open: bool,
+    large_preview_zoom: f32,
     edit_merchant: String,
     edit_amount_str: String,
     edit_datetime_str: String,
     edit_expense_type: String,
+    /// The expense field still holds an auto-applied value (not typed or picked by the user)
+    edit_expense_auto: bool,
+    edit_memo: String,
+    /// Tag being typed in the side panel, added on Enter
+    edit_tag_input: String,
+    merchant_popup_open: bool,
+    /// Decoded extra images of the selected row, keyed by (allocation address, length)
+    #[allow(clippy::type_complexity)]
+    extra_textures: HashMap<(usize, usize), Result<egui::TextureHandle, String>>,
+    /// Thumbnails of pending images keyed by (filename, byte length); Err = decode failure reason
+    thumbnail_cache: HashMap<(String, usize), Result<Thumbnail, String>>,
+    // Export confirmation state
+    pending_export: Option<ExportKind>,
+    export_issues: Vec<(usize, String)>,
+    /// "규칙 관리" window visibility
+    rules_window_open: bool,
+    /// "부가세 요약" window visibility
+    tax_window_open: bool,
+    /// PDF-producing export deferred one frame so "PDF 생성 중..." is drawn first
+    queued_pdf_export: Option<ExportKind>,
 }
 
 impl CardReceiptApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut state = AppState::new();
+        if let Some(storage) = cc.storage {
+            restore_session(&mut state, storage);
+        }
+        let budget_input = state.budget_limit.map(format_amount).unwrap_or_default();
+
         Self {
-            state: AppState::new(),
+            state,
             completed_queue: Arc::new(Mutex::new(Vec::new())),
             file_queue: Arc::new(Mutex::new(Vec::new())),
+            row_image_queue: Arc::new(Mutex::new(Vec::new())),
+            rerun_queue: Arc::new(Mutex::new(Vec::new())),
             ocr_remaining: Arc::new(Mutex::new(0)),
+            ocr_queue: VecDeque::new(),
+            ocr_in_flight: Arc::new(Mutex::new(0)),
+            ocr_cache: Arc::new(Mutex::new(VecDeque::new())),
             preview_texture: None,
+            preview_cache: VecDeque::new(),
             preview_loaded_for: None,
+            filter_inputs: FilterInputs::default(),
+            budget_input,
+            compare: None,
+            merge_dialog: None,
+            compare_zoom: 1.0,
+            large_preview_open: false,
+            large_preview_zoom: 1.0,
             edit_merchant: String::new(),
             edit_amount_str: String::new(),
             edit_datetime_str: String::new(),
             edit_expense_type: String::new(),
+            edit_expense_auto: false,
+            edit_memo: String::new(),
+            edit_tag_input: String::new(),
+            merchant_popup_open: false,
+            extra_textures: HashMap::new(),
+            thumbnail_cache: HashMap::new(),
+            pending_export: None,
+            export_issues: Vec::new(),
+            rules_window_open: false,
+            tax_window_open: false,
+            queued_pdf_export: None,
         }
     }
 
-    /// Start OCR processing for all pending images
+    /// Queue all pending images for OCR and start the first batch
     #[cfg(target_arch = "wasm32")]
     fn process_pending_images(&mut self, ctx: &egui::Context) {
         let pending: Vec<PendingImage> = self.state.pending_images.drain(..).collect();
@@ -74,68 +234,277 @@ impl CardReceiptApp {
             *remaining += pending.len();
         }
         self.state.ocr_in_progress = true;
+        self.ocr_queue.extend(pending);
+        self.launch_ocr_tasks(ctx);
+    }
+
+    /// Start queued OCR tasks until `ocr_concurrency` are running (called each frame)
+    #[cfg(target_arch = "wasm32")]
+    fn launch_ocr_tasks(&mut self, ctx: &egui::Context) {
+        let limit = self.state.ocr_concurrency.max(1);
+        let auto_rotate = self.state.auto_rotate;
+        while *self.ocr_in_flight.lock().unwrap() < limit {
+            let Some(image) = self.ocr_queue.pop_front() else {
+                break;
+            };
+            *self.ocr_in_flight.lock().unwrap() += 1;
 
-        for image in pending {
             let completed_queue = Arc::clone(&self.completed_queue);
             let remaining = Arc::clone(&self.ocr_remaining);
-            let filename = image.filename.clone();
-            let bytes = image.bytes;
+            let in_flight = Arc::clone(&self.ocr_in_flight);
+            let cache = Arc::clone(&self.ocr_cache);
+            let noise_tokens = self.state.expense_rules.noise_tokens.clone();
+            let amount_ceiling = self.state.amount_ceiling;
+            let grouping = self.state.digit_grouping;
             let ctx = ctx.clone();
 
             spawn_local(async move {
-                let result = match ocr::recognize_text(&bytes).await {
-                    Ok(text) => match parser::parse_receipt(&filename, &text) {
-                        Ok(mut txn) => {
-                            txn.image_bytes = bytes;
-                            Ok(txn)
-                        }
-                        Err(e) => {
-                            // Include first 300 chars of OCR text for debugging
-                            let preview: String = text.chars().take(300).collect();
-                            Err((
-                                filename.clone(),
-                                format!("파싱 실패: {} | OCR: {}", e, preview),
-                            ))
-                        }
-                    },
-                    Err(e) => Err((filename.clone(), format!("OCR 실패: {}", e))),
-                };
-
+                let result = recognize_image(
+                    image,
+                    auto_rotate,
+                    &cache,
+                    &noise_tokens,
+                    amount_ceiling,
+                    grouping,
+                )
+                .await;
                 completed_queue.lock().unwrap().push(result);
+                {
+                    let mut rem = remaining.lock().unwrap();
+                    *rem = rem.saturating_sub(1);
+                }
+                {
+                    let mut running = in_flight.lock().unwrap();
+                    *running = running.saturating_sub(1);
+                }
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Re-run OCR on a row's replaced image; the result refills that row
+    /// (matched by id) instead of adding a new one
+    #[cfg(target_arch = "wasm32")]
+    fn rerun_ocr(&mut self, ctx: &egui::Context, id: u64, image: PendingImage) {
+        *self.ocr_remaining.lock().unwrap() += 1;
+        self.state.ocr_in_progress = true;
+
+        let rerun_queue = Arc::clone(&self.rerun_queue);
+        let remaining = Arc::clone(&self.ocr_remaining);
+        let cache = Arc::clone(&self.ocr_cache);
+        let noise_tokens = self.state.expense_rules.noise_tokens.clone();
+        let auto_rotate = self.state.auto_rotate;
+        let amount_ceiling = self.state.amount_ceiling;
+        let grouping = self.state.digit_grouping;
+        let ctx = ctx.clone();
+
+        spawn_local(async move {
+            let result = recognize_image(
+                image,
+                auto_rotate,
+                &cache,
+                &noise_tokens,
+                amount_ceiling,
+                grouping,
+            )
+            .await;
+            rerun_queue.lock().unwrap().push((id, result));
+            {
                 let mut rem = remaining.lock().unwrap();
                 *rem = rem.saturating_sub(1);
-                ctx.request_repaint();
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    /// Swap the image of the row with `id` for a picked file,
+    /// then re-run OCR on it when `rerun_ocr_on_replace` is on
+    fn replace_image(&mut self, ctx: &egui::Context, id: u64, name: String, bytes: Vec<u8>) {
+        let Some(idx) = self.state.position_of(id) else {
+            return;
+        };
+        let old_len = self.state.transactions[idx].image_bytes.len();
+        let msg = if !has_image_signature(&bytes) {
+            Some(format!("지원하지 않는 이미지입니다: {}", name))
+        } else if self.loaded_image_bytes() - old_len + bytes.len() > IMAGE_BYTES_LIMIT {
+            Some(format!(
+                "메모리 부족 위험으로 {}(으)로 교체하지 않았습니다",
+                name
+            ))
+        } else {
+            None
+        };
+        if let Some(msg) = msg {
+            self.state.status_message = msg.clone();
+            self.state.error_messages.push(msg);
+            return;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if self.state.rerun_ocr_on_replace {
+            self.rerun_ocr(
+                ctx,
+                id,
+                PendingImage {
+                    filename: name.clone(),
+                    bytes: bytes.clone(),
+                    adjust: ImageAdjust::default(),
+                },
+            );
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = ctx;
+
+        self.state.status_message = format!("이미지를 {}(으)로 교체했습니다", name);
+        self.state.replace_image(idx, name, bytes);
+        self.preview_loaded_for = None;
+    }
+
+    /// Open the file picker for the row at `idx`; picked images go through
+    /// `row_image_queue`. A replacement takes only the first image.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+    fn pick_row_images(&self, idx: usize, action: RowImageAction) {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let queue = Arc::clone(&self.row_image_queue);
+            let id = self.state.transactions[idx].id;
+            spawn_local(async move {
+                match ocr::pick_files().await {
+                    Ok(files) => {
+                        let images = files.into_iter().filter(|(name, _)| is_image_file(name));
+                        let take = match action {
+                            RowImageAction::Replace => 1,
+                            RowImageAction::Attach => usize::MAX,
+                        };
+                        let mut q = queue.lock().unwrap();
+                        for (name, bytes) in images.take(take) {
+                            q.push((id, action, name, bytes));
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("File picker error: {}", e);
+                    }
+                }
             });
         }
     }
 
+    /// Attach a picked file to the row with `id` as an extra image
+    fn attach_image(&mut self, id: u64, name: String, bytes: Vec<u8>) {
+        let Some(idx) = self.state.position_of(id) else {
+            return;
+        };
+        let msg = if !has_image_signature(&bytes) {
+            format!("지원하지 않는 이미지입니다: {}", name)
+        } else if self.loaded_image_bytes() + bytes.len() > IMAGE_BYTES_LIMIT {
+            format!("메모리 부족 위험으로 {}을(를) 첨부하지 않았습니다", name)
+        } else {
+            self.state.add_extra_image(idx, bytes);
+            self.state.status_message = format!("{}을(를) 첨부했습니다", name);
+            return;
+        };
+        self.state.status_message = msg.clone();
+        self.state.error_messages.push(msg);
+    }
+
+    /// Add uploaded files to the OCR queue, skipping exact duplicates and
+    /// rejecting files whose content is not a JPEG/PNG despite the extension.
+    /// CSV files are imported as rows instead (see `import_csv`).
+    fn enqueue_files(&mut self, files: Vec<(String, Vec<u8>)>) {
+        let (csv_files, files): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|(name, _)| is_csv_file(name));
+        for (name, bytes) in csv_files {
+            self.import_csv(&name, &bytes);
+        }
+
+        let mut skipped = Vec::new();
+        let mut refused = 0;
+        let mut loaded = self.loaded_image_bytes();
+        for (name, bytes) in files {
+            if loaded + bytes.len() > IMAGE_BYTES_LIMIT {
+                refused += 1;
+            } else if !has_image_signature(&bytes) {
+                let msg = format!("지원하지 않는 이미지입니다: {}", name);
+                self.state.status_message = msg.clone();
+                self.state.error_messages.push(msg);
+            } else if self.state.is_duplicate_image(&name, &bytes) {
+                skipped.push(name);
+            } else {
+                loaded += bytes.len();
+                self.state.pending_images.push(PendingImage {
+                    filename: name,
+                    bytes,
+                    adjust: ImageAdjust::default(),
+                });
+            }
+        }
+        if !skipped.is_empty() {
+            self.state.status_message = format!("중복 파일 건너뜀: {}", skipped.join(", "));
+        }
+        if refused > 0 {
+            let msg = format!(
+                "메모리 부족 위험으로 {}개 파일을 추가하지 않았습니다. 결과를 내보내고 초기화한 뒤 나눠서 처리하세요",
+                refused
+            );
+            self.state.status_message = msg.clone();
+            self.state.error_messages.push(msg);
+        }
+    }
+
+    /// Image bytes held in memory: queued, in the OCR queue, and attached to rows
+    fn loaded_image_bytes(&self) -> usize {
+        self.state.loaded_image_bytes()
+            + self.ocr_queue.iter().map(|p| p.bytes.len()).sum::<usize>()
+    }
+
     /// Poll for completed OCR results (called each frame)
     fn poll_results(&mut self) {
         // Check completed transactions
         let mut completed = self.completed_queue.lock().unwrap();
         for result in completed.drain(..) {
             match result {
-                Ok(txn) => {
-                    self.state.transactions.push(txn);
+                Ok(mut txn) => {
+                    // Auto-apply the recommendation only when the field is still empty
+                    if self.state.auto_apply_expense
+                        && txn.expense_type.is_none()
+                        && let Some(rec) = self.state.expense_rules.detect(&txn.merchant)
+                    {
+                        txn.expense_type = Some(rec.label);
+                        txn.expense_auto = true;
+                    }
+                    self.state.push_transaction(txn);
                 }
-                Err((filename, error)) => {
+                Err(failed) => {
                     self.state
                         .error_messages
-                        .push(format!("{}: {}", filename, error));
+                        .push(format!("{}: {}", failed.filename, failed.reason));
+                    self.state.failed_images.push(failed);
                 }
             }
         }
         drop(completed);
 
-        // Check for newly picked files
-        let mut files = self.file_queue.lock().unwrap();
-        for (name, bytes) in files.drain(..) {
-            self.state.pending_images.push(PendingImage {
-                filename: name,
-                bytes,
-            });
+        // Re-recognized images from "이미지 교체"
+        let reruns: Vec<_> = self.rerun_queue.lock().unwrap().drain(..).collect();
+        for (id, result) in reruns {
+            let Some(idx) = self.state.position_of(id) else {
+                continue;
+            };
+            match result {
+                Ok(txn) => {
+                    self.state.apply_recognized(idx, txn);
+                    self.preview_loaded_for = None;
+                }
+                Err(failed) => self
+                    .state
+                    .error_messages
+                    .push(format!("{}: {}", failed.filename, failed.reason)),
+            }
         }
-        drop(files);
+
+        // Check for newly picked files
+        let files: Vec<(String, Vec<u8>)> = self.file_queue.lock().unwrap().drain(..).collect();
+        self.enqueue_files(files);
 
         // Update progress status
         let remaining = *self.ocr_remaining.lock().unwrap();
@@ -158,28 +527,47 @@ impl CardReceiptApp {
                     self.state.error_messages.len()
                 );
             }
+            let review_count = self
+                .state
+                .transactions
+                .iter()
+                .filter(|t| t.needs_review)
+                .count();
+            if review_count > 0 {
+                self.state
+                    .status_message
+                    .push_str(&format!(" ({}개 확인 필요)", review_count));
+            }
         }
     }
 
     /// Update preview texture and edit fields when selection changes
     fn update_preview(&mut self, ctx: &egui::Context) {
-        // Validate selected_index
-        if let Some(idx) = self.state.selected_index
-            && idx >= self.state.transactions.len()
-        {
-            self.state.selected_index = None;
+        // Drop a selection whose row is gone
+        let selected = self.state.selected_index();
+        if selected.is_none() {
+            self.state.selected_id = None;
         }
 
-        if self.state.selected_index != self.preview_loaded_for {
-            if let Some(idx) = self.state.selected_index {
+        // Keyed by row id, so re-sorting keeps the panel (and unsaved edits) as is
+        if self.state.selected_id != self.preview_loaded_for {
+            if let Some(idx) = selected {
                 let txn = &self.state.transactions[idx];
                 self.edit_merchant = txn.merchant.clone();
-                self.edit_amount_str = table::format_amount(txn.amount);
-                self.edit_datetime_str = txn.datetime.format("%Y.%m.%d %H:%M").to_string();
+                self.edit_amount_str = format_amount(txn.amount);
+                // Leave the field empty for rows whose date was never parsed
+                self.edit_datetime_str = if txn.datetime == unset_datetime() {
+                    String::new()
+                } else {
+                    txn.datetime.format(EDIT_DATETIME_FORMAT).to_string()
+                };
                 self.edit_expense_type = txn.expense_type.clone().unwrap_or_default();
-                self.preview_texture =
-                    decode_image_to_texture(ctx, &txn.filename, &txn.image_bytes);
-                self.preview_loaded_for = Some(idx);
+                self.edit_expense_auto = txn.expense_auto;
+                self.edit_memo = txn.memo.clone().unwrap_or_default();
+                self.edit_tag_input.clear();
+                self.merchant_popup_open = false;
+                self.preview_texture = Some(self.cached_preview(ctx, idx));
+                self.preview_loaded_for = self.state.selected_id;
             } else {
                 self.preview_loaded_for = None;
                 self.preview_texture = None;
@@ -187,20 +575,82 @@ impl CardReceiptApp {
         }
     }
 
+    /// Decoded `extra_images` of a row, cached while the row stays selected
+    fn extra_previews(
+        &mut self,
+        ctx: &egui::Context,
+        idx: usize,
+    ) -> Vec<Result<egui::TextureHandle, String>> {
+        let extras = &self.state.transactions[idx].extra_images;
+        // Keyed by allocation, so the megabytes aren't re-hashed every frame
+        let keys: Vec<(usize, usize)> = extras
+            .iter()
+            .map(|b| (b.as_ptr() as usize, b.len()))
+            .collect();
+        self.extra_textures.retain(|k, _| keys.contains(k));
+        keys.into_iter()
+            .zip(extras)
+            .enumerate()
+            .map(|(k, (key, bytes))| {
+                self.extra_textures
+                    .entry(key)
+                    .or_insert_with(|| {
+                        decode_image_to_texture(
+                            ctx,
+                            &format!("extra_{}", k),
+                            bytes,
+                            PREVIEW_MAX_SIDE,
+                        )
+                    })
+                    .clone()
+            })
+            .collect()
+    }
+
+    /// Preview texture for a row, from the cache when the same image was shown recently
+    fn cached_preview(
+        &mut self,
+        ctx: &egui::Context,
+        idx: usize,
+    ) -> Result<egui::TextureHandle, String> {
+        let txn = &self.state.transactions[idx];
+        let key = content_hash(&txn.image_bytes);
+        if let Some(pos) = self.preview_cache.iter().position(|(k, _)| *k == key) {
+            let entry = self.preview_cache.remove(pos).unwrap();
+            let texture = entry.1.clone();
+            self.preview_cache.push_back(entry);
+            return texture;
+        }
+        let texture =
+            decode_image_to_texture(ctx, &txn.filename, &txn.image_bytes, PREVIEW_MAX_SIDE);
+        // Dropping the evicted handle frees its GPU texture
+        if self.preview_cache.len() >= PREVIEW_CACHE_SIZE {
+            self.preview_cache.pop_front();
+        }
+        self.preview_cache.push_back((key, texture.clone()));
+        texture
+    }
+
     /// Apply edited fields back to the transaction
     fn apply_edits(&mut self, idx: usize) {
         if idx >= self.state.transactions.len() {
             return;
         }
+        self.state.push_undo();
 
         self.state.transactions[idx].merchant = self.edit_merchant.clone();
+        self.state.remember_merchant(&self.edit_merchant);
 
-        let amount_str = self.edit_amount_str.replace(",", "").replace(" ", "");
-        if let Ok(amount) = amount_str.parse::<u64>() {
+        let amount = parse_amount_input(&self.edit_amount_str);
+        if let Some(amount) = amount {
             self.state.transactions[idx].amount = amount;
         }
 
-        if let Ok(dt) = NaiveDateTime::parse_from_str(&self.edit_datetime_str, "%Y.%m.%d %H:%M") {
+        // Seconds are optional when typing; a date shown with seconds keeps them
+        let typed = self.edit_datetime_str.trim();
+        if let Ok(dt) = NaiveDateTime::parse_from_str(typed, EDIT_DATETIME_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(typed, "%Y.%m.%d %H:%M"))
+        {
             self.state.transactions[idx].datetime = dt;
         }
 
@@ -210,12 +660,1151 @@ impl CardReceiptApp {
         } else {
             Some(self.edit_expense_type.clone())
         };
+        let memo = self.edit_memo.trim();
+        self.state.transactions[idx].memo = if memo.is_empty() {
+            None
+        } else {
+            Some(memo.to_string())
+        };
+
+        // Saving from the edit panel counts as user confirmation, once the
+        // required fields are filled; otherwise the row stays flagged with what is missing
+        let txn = &mut self.state.transactions[idx];
+        txn.expense_auto = false;
+        txn.recheck_review(amount.is_none().then_some(self.edit_amount_str.as_str()));
+    }
+
+    /// Append the rows of an exported CSV (no images) to continue earlier work
+    fn import_csv(&mut self, name: &str, bytes: &[u8]) {
+        match crate::model::from_csv(&String::from_utf8_lossy(bytes)) {
+            Ok(rows) => {
+                let count = rows.len();
+                for txn in rows {
+                    self.state.push_transaction(txn);
+                }
+                self.state.sort_transactions();
+                self.state.status_message = format!("{}에서 {}건을 가져왔습니다", name, count);
+            }
+            Err(e) => {
+                let msg = format!("CSV 가져오기 실패 ({}): {}", name, e);
+                self.state.status_message = msg.clone();
+                self.state.error_messages.push(msg);
+            }
+        }
+    }
+
+    /// Undo the last row edit and reload the side panel from the restored row
+    fn undo(&mut self) {
+        if self.state.undo() {
+            self.preview_loaded_for = None;
+            self.state.status_message = "마지막 수정을 되돌렸습니다".into();
+        }
+    }
+
+    /// Thumbnail grid of queued images with a remove button on each,
+    /// so users can check the upload before running OCR
+    fn show_pending_thumbnails(&mut self, ui: &mut egui::Ui) {
+        // Drop cached thumbnails for images no longer queued, decode a few new ones
+        let keys: Vec<(String, usize)> = self
+            .state
+            .pending_images
+            .iter()
+            .map(|p| (p.filename.clone(), p.bytes.len()))
+            .collect();
+        self.thumbnail_cache.retain(|k, _| keys.contains(k));
+        let mut decoded = 0;
+        for (image, key) in self.state.pending_images.iter().zip(&keys) {
+            if decoded >= THUMBNAILS_PER_FRAME {
+                ui.ctx().request_repaint();
+                break;
+            }
+            if !self.thumbnail_cache.contains_key(key) {
+                // Decoded at thumbnail size first so slider changes preview quickly
+                let thumbnail =
+                    decode_image(&image.bytes, THUMBNAIL_MAX_SIDE).map(|source| Thumbnail {
+                        texture: image_to_texture(
+                            ui.ctx(),
+                            &format!("thumb_{}", image.filename),
+                            preprocess::adjust_image(source.clone(), &image.adjust),
+                        ),
+                        source,
+                    });
+                self.thumbnail_cache.insert(key.clone(), thumbnail);
+                decoded += 1;
+            }
+        }
+
+        ui.label(format!(
+            "OCR 대기 중인 이미지 {}개 - 확인 후 'OCR 인식 시작'을 누르세요",
+            keys.len()
+        ));
+        ui.add_space(4.0);
+
+        let mut remove = None;
+        let mut adjusted = Vec::new();
+        let pending = &mut self.state.pending_images;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (i, key) in keys.iter().enumerate() {
+                    ui.group(|ui| {
+                        ui.set_width(THUMBNAIL_MAX_SIDE as f32);
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&key.0).small())
+                                    .on_hover_text(&key.0);
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("✕").clicked() {
+                                            remove = Some(i);
+                                        }
+                                    },
+                                );
+                            });
+                            match self.thumbnail_cache.get(key) {
+                                Some(Ok(Thumbnail { texture, .. })) => {
+                                    let [tw, th] = texture.size();
+                                    let scale = THUMBNAIL_MAX_SIDE as f32 / tw.max(th) as f32;
+                                    ui.image(egui::load::SizedTexture::new(
+                                        texture.id(),
+                                        egui::vec2(tw as f32 * scale, th as f32 * scale),
+                                    ));
+                                }
+                                Some(Err(reason)) => {
+                                    ui.colored_label(egui::Color32::GRAY, "미리보기 불가")
+                                        .on_hover_text(reason.as_str());
+                                }
+                                None => {
+                                    ui.spinner();
+                                }
+                            }
+                            if adjust_controls(ui, i, &mut pending[i].adjust) {
+                                adjusted.push(i);
+                            }
+                        });
+                    });
+                }
+            });
+        });
+
+        for i in adjusted {
+            if let Some(Ok(thumbnail)) = self.thumbnail_cache.get_mut(&keys[i]) {
+                let image = &self.state.pending_images[i];
+                thumbnail.texture = image_to_texture(
+                    ui.ctx(),
+                    &format!("thumb_{}", image.filename),
+                    preprocess::adjust_image(thumbnail.source.clone(), &image.adjust),
+                );
+            }
+        }
+        if let Some(i) = remove {
+            self.state.pending_images.remove(i);
+        }
+    }
+
+    /// Rows that look wrong (future dates) with one-click fixes
+    fn show_review_section(&mut self, ui: &mut egui::Ui) {
+        let future = self.state.future_dated(local_now());
+        if future.is_empty() {
+            return;
+        }
+
+        let mut fix_year = None;
+        ui.separator();
+        ui.collapsing(format!("확인 필요 ({}건)", future.len()), |ui| {
+            for &idx in &future {
+                let txn = &self.state.transactions[idx];
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 180, 80),
+                        format!(
+                            "#{} {} {} - 미래 날짜",
+                            idx + 1,
+                            txn.datetime.format("%Y.%m.%d %H:%M"),
+                            txn.merchant
+                        ),
+                    );
+                    if ui
+                        .small_button("연도 보정")
+                        .on_hover_text("1년을 뺍니다 (2자리 연도 오인식 보정)")
+                        .clicked()
+                    {
+                        fix_year = Some(idx);
+                    }
+                });
+            }
+        });
+
+        if let Some(idx) = fix_year {
+            self.state.subtract_year(idx);
+            // Refresh the edit fields if the fixed row is open
+            self.preview_loaded_for = None;
+        }
+    }
+
+    /// Autocomplete popup under the merchant field, fed by previously seen merchants
+    fn show_merchant_autocomplete(&mut self, ui: &egui::Ui, field: &egui::Response) {
+        if field.changed() || field.gained_focus() {
+            self.merchant_popup_open = true;
+        }
+        if !self.merchant_popup_open {
+            return;
+        }
+
+        let suggestions = self.state.merchant_suggestions(&self.edit_merchant, 8);
+        if suggestions.is_empty() {
+            return;
+        }
+
+        let mut picked = None;
+        let area = egui::Area::new(egui::Id::new("merchant_autocomplete"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(field.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(field.rect.width());
+                    for name in &suggestions {
+                        if ui.selectable_label(false, name).clicked() {
+                            picked = Some(name.clone());
+                        }
+                    }
+                });
+            });
+
+        // Clicking a suggestion steals focus from the text field on press,
+        // so keep the popup open while the pointer is over it.
+        if let Some(name) = picked {
+            self.edit_merchant = name;
+            self.merchant_popup_open = false;
+        } else if !field.has_focus() && !area.response.contains_pointer() {
+            self.merchant_popup_open = false;
+        }
+    }
+
+    /// Validate rows before exporting; ask for confirmation when problems are found
+    fn request_export(&mut self, kind: ExportKind) {
+        let mut issues = self.state.validate();
+        // Only rows that end up in a selected-PDF export matter for it
+        if kind == ExportKind::SelectedPdf && self.state.multi_select {
+            issues.retain(|(idx, _)| self.state.selected_rows.contains(idx));
+        }
+        // Rows left out of the export can't break it
+        let exported: Vec<bool> = self
+            .state
+            .transactions
+            .iter()
+            .map(|t| self.state.is_exported(t))
+            .collect();
+        issues.retain(|(idx, _)| exported[*idx]);
+        if issues.is_empty() {
+            self.run_export(kind);
+        } else {
+            self.export_issues = issues;
+            self.pending_export = Some(kind);
+        }
+    }
+
+    fn run_export(&mut self, kind: ExportKind) {
+        match kind {
+            ExportKind::Csv => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let csv = self.state.to_csv();
+                    if let Err(e) = web_download::download_csv("카드사용내역.csv", &csv) {
+                        self.state.status_message = format!("CSV 다운로드 실패: {}", e);
+                    }
+                }
+            }
+            ExportKind::Json => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let result = self.state.to_json().and_then(|json| {
+                        web_download::download_file(
+                            "카드사용내역.json",
+                            json.as_bytes(),
+                            "application/json",
+                        )
+                        .map_err(|e| format!("JSON 다운로드 실패: {}", e))
+                    });
+                    if let Err(e) = result {
+                        self.state.status_message = e;
+                    }
+                }
+            }
+            ExportKind::Html => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let html = self.state.to_html();
+                    if let Err(e) = web_download::download_file(
+                        "카드사용내역.html",
+                        html.as_bytes(),
+                        "text/html;charset=utf-8",
+                    ) {
+                        self.state.status_message = format!("HTML 다운로드 실패: {}", e);
+                    }
+                }
+            }
+            ExportKind::Zip | ExportKind::SelectedPdf => {
+                // PDF generation blocks the main thread; show the state before starting
+                let (rows, failed) = self.pdf_pages(kind);
+                let pages =
+                    rows.iter().map(|t| 1 + t.extra_images.len()).sum::<usize>() + failed.len();
+                self.state.status_message = format!("PDF 생성 중... ({}쪽)", pages);
+                self.queued_pdf_export = Some(kind);
+            }
+        }
+    }
+
+    /// Receipts and failed images that go into a PDF export. Failed images are only
+    /// appended to full exports, not to a checked-rows selection.
+    fn pdf_pages(&self, kind: ExportKind) -> (Vec<&CardTransaction>, Vec<&FailedImage>) {
+        match kind {
+            ExportKind::SelectedPdf if self.state.multi_select => {
+                (self.state.export_selection(), Vec::new())
+            }
+            _ => (self.state.export_rows(), self.state.failed_for_export()),
+        }
+    }
+
+    /// Run a deferred PDF/ZIP export (one frame after `run_export` queued it)
+    #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+    fn run_pdf_export(&mut self, kind: ExportKind) {
+        let mut pages_done = 0;
+        let progress = |done: usize, total: usize| {
+            pages_done = done;
+            log::debug!("PDF 생성 중... ({}/{})", done, total);
+        };
+        match kind {
+            ExportKind::Csv | ExportKind::Json | ExportKind::Html => {}
+            ExportKind::Zip => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let csv = self.state.to_csv();
+                    let (rows, failed) = self.pdf_pages(kind);
+                    // Failed images follow the receipts, matching their PDF page numbers
+                    let images: Vec<(&str, &[u8])> = rows
+                        .iter()
+                        .map(|t| (t.filename.as_str(), &t.image_bytes[..]))
+                        .chain(
+                            failed
+                                .iter()
+                                .map(|f| (f.filename.as_str(), f.bytes.as_slice())),
+                        )
+                        .collect();
+                    let category_csvs = if self.state.export_csv_by_category {
+                        self.state.to_csv_by_category()
+                    } else {
+                        Vec::new()
+                    };
+                    let result = crate::pdf_export::generate_receipts_pdf(
+                        &rows,
+                        &failed,
+                        self.state.pdf_layout,
+                        self.state.digit_grouping,
+                        self.state.pdf_include_filename,
+                        progress,
+                    )
+                    .map_err(|e| format!("PDF 생성 실패: {}", e))
+                    .and_then(|pdf_bytes| {
+                        web_download::download_receipt_bundle(
+                            &images,
+                            csv.as_bytes(),
+                            &pdf_bytes,
+                            &category_csvs,
+                            self.state.zip_keep_filenames,
+                            "영수증모음.zip",
+                        )
+                        .map_err(|e| format!("ZIP 다운로드 실패: {}", e))
+                    });
+                    self.state.status_message = match result {
+                        Ok(()) => format!("ZIP 내보내기 완료 (PDF {}쪽)", pages_done),
+                        Err(e) => e,
+                    };
+                }
+            }
+            ExportKind::SelectedPdf => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let (selection, failed) = self.pdf_pages(kind);
+                    let result = crate::pdf_export::generate_receipts_pdf(
+                        &selection,
+                        &failed,
+                        self.state.pdf_layout,
+                        self.state.digit_grouping,
+                        self.state.pdf_include_filename,
+                        progress,
+                    )
+                    .map_err(|e| format!("PDF 생성 실패: {}", e))
+                    .and_then(|pdf_bytes| {
+                        web_download::download_file("영수증.pdf", &pdf_bytes, "application/pdf")
+                            .map_err(|e| format!("PDF 다운로드 실패: {}", e))
+                    });
+                    self.state.status_message = match result {
+                        Ok(()) => format!("PDF 내보내기 완료 ({}쪽)", pages_done),
+                        Err(e) => e,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Confirmation dialog listing validation problems before an export
+    fn show_export_confirm(&mut self, ctx: &egui::Context) {
+        let Some(kind) = self.pending_export else {
+            return;
+        };
+        let mut proceed = false;
+        let mut cancel = false;
+
+        egui::Window::new("내보내기 확인")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{}개 문제가 발견되었습니다. 그래도 내보내시겠습니까?",
+                    self.export_issues.len()
+                ));
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for (idx, msg) in &self.export_issues {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 180, 80),
+                                format!("#{}: {}", idx + 1, msg),
+                            );
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("그래도 내보내기").clicked() {
+                        proceed = true;
+                    }
+                    if ui.button("취소").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if proceed {
+            self.run_export(kind);
+        }
+        if proceed || cancel {
+            self.pending_export = None;
+            self.export_issues.clear();
+        }
+    }
+
+    /// Expense rule priority editor: the first enabled matching rule wins
+    fn show_rules_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.rules_window_open;
+        let mut move_request = None;
+        let mut reset = false;
+        let mut color_change: Option<(String, [u8; 3])> = None;
+        let mut noise = self.state.expense_rules.noise_tokens.join(", ");
+
+        egui::Window::new("비용 규칙 관리")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label("위에 있는 규칙이 먼저 적용됩니다");
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let count = self.state.expense_rules.rules.len();
+                    for (i, rule) in self.state.expense_rules.rules.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(i > 0, egui::Button::new("▲")).clicked() {
+                                move_request = Some((i, true));
+                            }
+                            if ui
+                                .add_enabled(i + 1 < count, egui::Button::new("▼"))
+                                .clicked()
+                            {
+                                move_request = Some((i, false));
+                            }
+                            ui.checkbox(&mut rule.enabled, "");
+                            let mut color = self
+                                .state
+                                .expense_colors
+                                .get(&rule.label)
+                                .copied()
+                                .unwrap_or(DEFAULT_EXPENSE_COLOR);
+                            if egui::color_picker::color_edit_button_srgb(ui, &mut color)
+                                .on_hover_text("표에서 이 비용종류를 표시할 색")
+                                .changed()
+                            {
+                                color_change = Some((rule.label.clone(), color));
+                            }
+                            ui.strong(&rule.label)
+                                .on_hover_text(rule.keywords.join(", "));
+                            ui.colored_label(egui::Color32::GRAY, &rule.category);
+                        });
+                    }
+                });
+                ui.separator();
+                ui.label("가맹점명에서 지울 문구 (쉼표로 구분)");
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut noise)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("예: [이벤트], 본점"),
+                    )
+                    .changed()
+                {
+                    self.state.expense_rules.noise_tokens = noise
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect();
+                }
+                ui.separator();
+                if ui.button("기본값으로").clicked() {
+                    reset = true;
+                }
+            });
+
+        if let Some((idx, up)) = move_request {
+            self.state.expense_rules.move_rule(idx, up);
+        }
+        if let Some((label, color)) = color_change {
+            self.state.set_expense_color(&label, color);
+        }
+        if reset {
+            self.state.expense_rules = crate::expense::ExpenseRules::default();
+        }
+        self.rules_window_open = open;
+    }
+
+    /// 공급가액/부가세/합계 of the rows in the current total
+    fn show_tax_window(&mut self, ctx: &egui::Context) {
+        if !self.tax_window_open {
+            return;
+        }
+        let summary = self.state.tax_summary();
+        let grouping = self.state.digit_grouping;
+        let foreign = self
+            .state
+            .transactions
+            .iter()
+            .filter(|t| t.foreign_amount.is_some() && self.state.counts_in_total(t))
+            .count();
+
+        egui::Window::new("부가세 요약")
+            .open(&mut self.tax_window_open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("tax_summary")
+                    .num_columns(2)
+                    .spacing([24.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label("공급가액");
+                        ui.label(format!(
+                            "{}원",
+                            format_amount_grouped(summary.supply, grouping)
+                        ));
+                        ui.end_row();
+                        ui.label("부가세");
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{}원",
+                                format_amount_grouped(summary.vat, grouping)
+                            ));
+                            if summary.estimated > 0 {
+                                ui.colored_label(egui::Color32::from_rgb(255, 180, 80), "추정");
+                            }
+                        });
+                        ui.end_row();
+                        ui.strong("합계");
+                        ui.strong(format!(
+                            "{}원",
+                            format_amount_grouped(summary.total, grouping)
+                        ));
+                        ui.end_row();
+                    });
+                if summary.estimated > 0 {
+                    ui.separator();
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        format!(
+                            "부가세가 없는 {}건은 금액의 1/11(10% 포함)로 추정했습니다",
+                            summary.estimated
+                        ),
+                    );
+                }
+                if foreign > 0 {
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        format!("해외 결제 {}건은 제외했습니다", foreign),
+                    );
+                }
+            });
+    }
+
+    /// "전체" plus one tab per transaction month; picking a month filters the
+    /// table (and its totals) to it. Hidden for single-month batches.
+    fn show_month_tabs(&mut self, ui: &mut egui::Ui) {
+        let months = self.state.months();
+        let filter = &mut self.state.filter;
+        if months.len() < 2 && filter.month.is_none() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.selectable_value(&mut filter.month, None, "전체");
+            for (year, month) in months {
+                ui.selectable_value(
+                    &mut filter.month,
+                    Some((year, month)),
+                    format!("{}-{:02}", year, month),
+                );
+            }
+        });
+    }
+
+    /// Search, expense type, date range and amount range filters above the table
+    fn show_filter_bar(&mut self, ui: &mut egui::Ui) {
+        let tags: Vec<String> = self
+            .state
+            .all_tags()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let filter = &mut self.state.filter;
+        let inputs = &mut self.filter_inputs;
+        ui.horizontal_wrapped(|ui| {
+            ui.label("🔍");
+            ui.add(
+                egui::TextEdit::singleline(&mut filter.search)
+                    .desired_width(120.0)
+                    .hint_text("가맹점·메모·태그 검색"),
+            );
+
+            let expense_text = filter.expense.clone().unwrap_or_else(|| "전체".to_string());
+            egui::ComboBox::from_id_salt("filter_expense")
+                .selected_text(expense_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.expense, None, "전체");
+                    for label in expense::all_expense_labels()
+                        .iter()
+                        .copied()
+                        .chain([UNCATEGORIZED_LABEL])
+                    {
+                        ui.selectable_value(&mut filter.expense, Some(label.to_string()), label);
+                    }
+                });
+
+            if !tags.is_empty() || filter.tag.is_some() {
+                let tag_text = filter
+                    .tag
+                    .as_ref()
+                    .map_or("태그 전체".to_string(), |t| format!("#{}", t));
+                egui::ComboBox::from_id_salt("filter_tag")
+                    .selected_text(tag_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut filter.tag, None, "태그 전체");
+                        for tag in tags {
+                            let text = format!("#{}", tag);
+                            ui.selectable_value(&mut filter.tag, Some(tag), text);
+                        }
+                    });
+            }
+
+            ui.separator();
+            ui.label("날짜");
+            if range_input(ui, &mut inputs.date_from, "2026-01-15", |t| {
+                parse_filter_date(t).is_some()
+            }) {
+                filter.date_from = parse_filter_date(&inputs.date_from);
+            }
+            ui.label("~");
+            if range_input(ui, &mut inputs.date_to, "2026-01-31", |t| {
+                parse_filter_date(t).is_some()
+            }) {
+                filter.date_to = parse_filter_date(&inputs.date_to);
+            }
+
+            ui.separator();
+            ui.label("금액");
+            if range_input(ui, &mut inputs.amount_min, "10,000", |t| {
+                parse_amount_input(t).is_some()
+            }) {
+                filter.amount_min = parse_amount_input(&inputs.amount_min);
+            }
+            ui.label("~");
+            if range_input(ui, &mut inputs.amount_max, "", |t| {
+                parse_amount_input(t).is_some()
+            }) {
+                filter.amount_max = parse_amount_input(&inputs.amount_max);
+            }
+
+            if filter.is_active() && ui.button("필터 해제").clicked() {
+                *filter = Default::default();
+                *inputs = FilterInputs::default();
+            }
+        });
+    }
+
+    /// Decode the two checked rows' images for the comparison window
+    fn open_compare(&mut self, ctx: &egui::Context) {
+        let rows: Vec<usize> = self.state.selected_rows.iter().copied().collect();
+        let [a, b] = rows[..] else {
+            return;
+        };
+        let decode = |idx: usize| {
+            let t = &self.state.transactions[idx];
+            let texture = if t.image_bytes.is_empty() {
+                Err("원본 이미지가 저장되지 않은 거래입니다".to_string())
+            } else {
+                decode_image_to_texture(ctx, &t.filename, &t.image_bytes, PREVIEW_MAX_SIDE)
+            };
+            (t.id, texture)
+        };
+        self.compare = Some([decode(a), decode(b)]);
+        self.compare_zoom = 1.0;
+    }
+
+    /// Two receipts side by side with one zoom, to decide which duplicate to keep
+    fn show_compare_window(&mut self, ctx: &egui::Context) {
+        let Some(pair) = &self.compare else {
+            return;
+        };
+        let mut open = true;
+        let mut zoom = self.compare_zoom;
+        let state = &self.state;
+        let grouping = state.digit_grouping;
+
+        egui::Window::new("영수증 비교")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(ctx.available_rect().width() * 0.8)
+            .default_height(ctx.available_rect().height() * 0.8)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("−").clicked() {
+                        zoom = (zoom / 1.25).max(0.25);
+                    }
+                    ui.label(format!("{:.0}%", zoom * 100.0));
+                    if ui.button("+").clicked() {
+                        zoom = (zoom * 1.25).min(8.0);
+                    }
+                    if ui.button("맞춤").clicked() {
+                        zoom = 1.0;
+                    }
+                });
+                ui.separator();
+
+                let column_width = (ui.available_width() - ui.spacing().item_spacing.x) / 2.0;
+                ui.columns(2, |columns| {
+                    for (ui, (id, texture)) in columns.iter_mut().zip(pair) {
+                        if let Some(idx) = state.position_of(*id) {
+                            let t = &state.transactions[idx];
+                            ui.strong(format!("#{} {}", idx + 1, t.merchant));
+                            ui.label(format!(
+                                "{}  {}원",
+                                t.datetime.format("%Y.%m.%d %H:%M"),
+                                format_amount_grouped(t.amount, grouping)
+                            ));
+                            ui.colored_label(egui::Color32::GRAY, &t.filename);
+                        } else {
+                            ui.colored_label(egui::Color32::GRAY, "삭제된 거래입니다");
+                        }
+                        ui.separator();
+                        match texture {
+                            Ok(texture) => {
+                                let [tw, th] = texture.size();
+                                let size = egui::vec2(tw as f32, th as f32)
+                                    * (column_width / tw as f32)
+                                    * zoom;
+                                egui::ScrollArea::both()
+                                    .id_salt(("compare_scroll", *id))
+                                    .show(ui, |ui| {
+                                        ui.image(egui::load::SizedTexture::new(texture.id(), size));
+                                    });
+                            }
+                            Err(reason) => {
+                                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), reason);
+                            }
+                        }
+                    }
+                });
+            });
+
+        self.compare_zoom = zoom;
+        if !open {
+            self.compare = None;
+        }
+    }
+
+    /// Pick which row's amount, date and image a merge keeps, then merge
+    fn show_merge_dialog(&mut self, ctx: &egui::Context) {
+        let Some(([a, b], choices)) = &mut self.merge_dialog else {
+            return;
+        };
+        let (Some(a), Some(b)) = (self.state.position_of(*a), self.state.position_of(*b)) else {
+            self.merge_dialog = None;
+            return;
+        };
+        let (first, second) = (&self.state.transactions[a], &self.state.transactions[b]);
+        let label = |idx: usize, t: &CardTransaction| format!("#{} {}", idx + 1, t.merchant);
+        let grouping = self.state.digit_grouping;
+        let mut open = true;
+        let mut merge = false;
+        let mut cancel = false;
+
+        egui::Window::new("거래 병합")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("merge_choices")
+                    .num_columns(2)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label("금액");
+                        ui.vertical(|ui| {
+                            ui.radio_value(
+                                &mut choices.amount,
+                                MergeAmount::Sum,
+                                format!(
+                                    "합계 {}원",
+                                    format_amount_grouped(first.amount + second.amount, grouping)
+                                ),
+                            );
+                            for (source, t) in
+                                [(MergeSource::First, first), (MergeSource::Second, second)]
+                            {
+                                ui.radio_value(
+                                    &mut choices.amount,
+                                    MergeAmount::Keep(source),
+                                    format!("{}원", format_amount_grouped(t.amount, grouping)),
+                                );
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("날짜");
+                        ui.vertical(|ui| {
+                            for (source, t) in
+                                [(MergeSource::First, first), (MergeSource::Second, second)]
+                            {
+                                ui.radio_value(
+                                    &mut choices.datetime,
+                                    source,
+                                    t.datetime.format("%Y.%m.%d %H:%M").to_string(),
+                                );
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("이미지");
+                        ui.vertical(|ui| {
+                            for (source, idx, t) in [
+                                (MergeSource::First, a, first),
+                                (MergeSource::Second, b, second),
+                            ] {
+                                ui.radio_value(&mut choices.image, source, label(idx, t))
+                                    .on_hover_text(&t.filename);
+                            }
+                        });
+                        ui.end_row();
+                    });
+                ui.colored_label(
+                    egui::Color32::GRAY,
+                    "가맹점명과 메모는 다르면 이어 붙이고, 나머지는 이미지를 고른 거래를 따릅니다. 다른 이미지는 첨부로 남습니다",
+                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("병합").clicked() {
+                        merge = true;
+                    }
+                    if ui.button("취소").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if merge {
+            let (_, choices) = self.merge_dialog.take().unwrap();
+            self.state.merge(a, b, choices);
+            self.preview_loaded_for = None;
+            self.state.status_message = "두 거래를 병합했습니다".into();
+        } else if cancel || !open {
+            self.merge_dialog = None;
+        }
+    }
+
+    /// Raw OCR text of a row, opened from the table's right-click menu
+    fn show_ocr_text_window(&mut self, ctx: &egui::Context) {
+        let Some((filename, text)) = &self.state.ocr_text_view else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(format!("OCR 텍스트 - {}", filename))
+            .id(egui::Id::new("ocr_text_view"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if ui.button("복사").clicked() {
+                    ui.ctx().copy_text(text.clone());
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if text.trim().is_empty() {
+                        ui.colored_label(egui::Color32::GRAY, "(인식된 텍스트 없음)");
+                    } else {
+                        ui.monospace(text.as_str());
+                    }
+                });
+            });
+        if !open {
+            self.state.ocr_text_view = None;
+        }
+    }
+
+    /// Full-size preview of the selected receipt, fitted to the viewport with zoom
+    fn show_large_preview(&mut self, ctx: &egui::Context) {
+        if !self.large_preview_open {
+            return;
+        }
+        let (Some(idx), Some(Ok(texture))) = (self.state.selected_index(), &self.preview_texture)
+        else {
+            self.large_preview_open = false;
+            return;
+        };
+        let mut open = true;
+        let viewport = ctx.available_rect().size() * 0.9;
+        let title = self
+            .state
+            .transactions
+            .get(idx)
+            .map(|t| t.filename.clone())
+            .unwrap_or_default();
+
+        egui::Window::new(format!("미리보기 - {}", title))
+            .id(egui::Id::new("large_preview"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(viewport.x)
+            .default_height(viewport.y)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("−").clicked() {
+                        self.large_preview_zoom = (self.large_preview_zoom / 1.25).max(0.25);
+                    }
+                    ui.label(format!("{:.0}%", self.large_preview_zoom * 100.0));
+                    if ui.button("+").clicked() {
+                        self.large_preview_zoom = (self.large_preview_zoom * 1.25).min(8.0);
+                    }
+                    if ui.button("맞춤").clicked() {
+                        self.large_preview_zoom = 1.0;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(txn) = self.state.transactions.get(idx)
+                        && ui
+                            .button("기본 뷰어로 열기")
+                            .on_hover_text("원본 이미지를 시스템 이미지 뷰어로 엽니다")
+                            .clicked()
+                        && let Err(e) = open_in_system_viewer(txn)
+                    {
+                        self.state.error_messages.push(e);
+                    }
+                });
+                ui.separator();
+
+                // Ctrl/Cmd + wheel zooms, plain wheel scrolls
+                let zoom_delta = ui.input(|i| i.zoom_delta());
+                if zoom_delta != 1.0 {
+                    self.large_preview_zoom =
+                        (self.large_preview_zoom * zoom_delta).clamp(0.25, 8.0);
+                }
+
+                let [tw, th] = texture.size();
+                let avail = ui.available_size().max(egui::vec2(1.0, 1.0));
+                let fit = (avail.x / tw as f32).min(avail.y / th as f32);
+                let size = egui::vec2(tw as f32, th as f32) * fit * self.large_preview_zoom;
+                egui::ScrollArea::both().show(ui, |ui| {
+                    let response = ui.image(egui::load::SizedTexture::new(texture.id(), size));
+                    if self.state.show_ocr_highlights
+                        && let Some(txn) = self.state.transactions.get(idx)
+                    {
+                        paint_field_highlights(ui, response.rect, txn);
+                    }
+                });
+            });
+
+        self.large_preview_open = open;
+    }
+}
+
+/// Time zones offered for the JSON export: (UTC offset in minutes, place names)
+const UTC_OFFSETS: [(i32, &str); 9] = [
+    (9 * 60, "서울/도쿄"),
+    (8 * 60, "베이징/싱가포르"),
+    (7 * 60, "방콕/하노이"),
+    (10 * 60, "시드니"),
+    (0, "런던"),
+    (60, "파리/베를린"),
+    (-5 * 60, "뉴욕"),
+    (-8 * 60, "로스앤젤레스"),
+    (-10 * 60, "하와이"),
+];
+
+/// "UTC+09:00 서울/도쿄"
+fn utc_offset_label(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let abs = minutes.abs();
+    let places = UTC_OFFSETS
+        .iter()
+        .find(|(m, _)| *m == minutes)
+        .map(|(_, p)| *p)
+        .unwrap_or("");
+    format!("UTC{}{:02}:{:02} {}", sign, abs / 60, abs % 60, places)
+        .trim_end()
+        .to_string()
+}
+
+/// Short text field for a filter bound or the budget; red text while the input doesn't parse.
+/// Returns true when the text changed.
+fn range_input(
+    ui: &mut egui::Ui,
+    text: &mut String,
+    hint: &str,
+    valid: impl Fn(&str) -> bool,
+) -> bool {
+    let invalid = !text.trim().is_empty() && !valid(text);
+    let mut edit = egui::TextEdit::singleline(text)
+        .desired_width(80.0)
+        .hint_text(hint);
+    if invalid {
+        edit = edit.text_color(egui::Color32::from_rgb(255, 100, 100));
+    }
+    ui.add(edit).changed()
+}
+
+/// Write the original image to a temp file and hand it to the OS viewer
+#[cfg(not(target_arch = "wasm32"))]
+fn open_in_system_viewer(txn: &CardTransaction) -> Result<(), String> {
+    let name = std::path::Path::new(&txn.filename)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "receipt.png".to_string());
+    let path = std::env::temp_dir().join(format!("card-receipt-{}", name));
+    std::fs::write(&path, &txn.image_bytes).map_err(|e| format!("임시 파일 저장 실패: {}", e))?;
+    open::that(&path).map_err(|e| format!("이미지 뷰어 실행 실패: {}", e))
+}
+
+/// Whether the OCR engine has finished loading (always true on native, which has no OCR)
+fn ocr_ready() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    return crate::ocr::is_ready();
+    #[cfg(not(target_arch = "wasm32"))]
+    true
+}
+
+/// Restore transactions and the user's sort preference from eframe storage.
+/// Images are not persisted (`image_bytes` is `#[serde(skip)]`).
+fn restore_session(state: &mut AppState, storage: &dyn eframe::Storage) {
+    if let Some(rules) = eframe::get_value(storage, STORAGE_EXPENSE_RULES) {
+        state.expense_rules = rules;
+    }
+    if let Some(colors) = eframe::get_value(storage, STORAGE_EXPENSE_COLORS) {
+        state.expense_colors = colors;
+    }
+    if let Some(columns) = eframe::get_value(storage, STORAGE_VISIBLE_COLUMNS) {
+        state.visible_columns = columns;
+    }
+    if let Some(ceiling) = eframe::get_value(storage, STORAGE_AMOUNT_CEILING) {
+        state.amount_ceiling = ceiling;
+    }
+    if let Some(limit) = eframe::get_value(storage, STORAGE_BUDGET_LIMIT) {
+        state.budget_limit = limit;
+    }
+    if let Some(column) = eframe::get_value(storage, STORAGE_SORT_COLUMN) {
+        state.sort_column = column;
+    }
+    if let Some(direction) = eframe::get_value(storage, STORAGE_SORT_DIRECTION) {
+        state.sort_direction = direction;
+    }
+    if let Some(transactions) =
+        eframe::get_value::<Vec<CardTransaction>>(storage, STORAGE_TRANSACTIONS)
+    {
+        state.transactions = transactions;
+        state.normalize_original_index();
+        state.assign_ids();
+        state.known_merchants.extend(
+            state
+                .transactions
+                .iter()
+                .map(|t| t.merchant.trim().to_string())
+                .filter(|m| !m.is_empty()),
+        );
+        // A restored session honors the saved sort rather than the post-OCR default
+        state.sort_transactions();
+        if !state.transactions.is_empty() {
+            state.status_message = format!(
+                "이전 세션 복원됨: {}개 거래 (이미지 제외)",
+                state.transactions.len()
+            );
+        }
     }
 }
 
 impl eframe::App for CardReceiptApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, STORAGE_TRANSACTIONS, &self.state.transactions);
+        eframe::set_value(storage, STORAGE_SORT_COLUMN, &self.state.sort_column);
+        eframe::set_value(storage, STORAGE_SORT_DIRECTION, &self.state.sort_direction);
+        eframe::set_value(storage, STORAGE_EXPENSE_RULES, &self.state.expense_rules);
+        eframe::set_value(storage, STORAGE_EXPENSE_COLORS, &self.state.expense_colors);
+        eframe::set_value(
+            storage,
+            STORAGE_VISIBLE_COLUMNS,
+            &self.state.visible_columns,
+        );
+        eframe::set_value(storage, STORAGE_AMOUNT_CEILING, &self.state.amount_ceiling);
+        eframe::set_value(storage, STORAGE_BUDGET_LIMIT, &self.state.budget_limit);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.poll_results();
+        let picked: Vec<_> = self.row_image_queue.lock().unwrap().drain(..).collect();
+        for (id, action, name, bytes) in picked {
+            match action {
+                RowImageAction::Replace => self.replace_image(ctx, id, name, bytes),
+                RowImageAction::Attach => self.attach_image(id, name, bytes),
+            }
+        }
+
+        // Drop-and-go: newly added images start OCR on their own when idle
+        #[cfg(target_arch = "wasm32")]
+        if self.state.auto_start_ocr
+            && ocr_ready()
+            && !self.state.ocr_in_progress
+            && !self.state.pending_images.is_empty()
+        {
+            self.process_pending_images(ctx);
+        }
+
+        // Run an export queued last frame, now that its "PDF 생성 중..." status is on screen
+        if let Some(kind) = self.queued_pdf_export.take() {
+            self.run_pdf_export(kind);
+        }
+
+        // Ctrl+Z (Cmd+Z) undoes the last row edit, unless a text field wants it
+        let undo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        if ctx.memory(|m| m.focused().is_none())
+            && ctx.input_mut(|i| i.consume_shortcut(&undo_shortcut))
+        {
+            self.undo();
+        }
+
+        // Refill free OCR slots as tasks complete
+        #[cfg(target_arch = "wasm32")]
+        self.launch_ocr_tasks(ctx);
 
         // Keep repainting while OCR is in progress
         if self.state.ocr_in_progress {
@@ -223,48 +1812,106 @@ impl eframe::App for CardReceiptApp {
         }
 
         // Handle drag-and-drop
-        ctx.input(|i| {
-            if !i.raw.dropped_files.is_empty() {
-                for file in &i.raw.dropped_files {
-                    if let Some(bytes) = &file.bytes {
-                        let name = file.name.clone();
-                        if is_image_file(&name) {
-                            self.state.pending_images.push(PendingImage {
-                                filename: name,
-                                bytes: bytes.to_vec(),
-                            });
-                        }
-                    }
-                }
-            }
+        let dropped: Vec<(String, Vec<u8>)> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter(|file| is_image_file(&file.name) || is_csv_file(&file.name))
+                .filter_map(|file| Some((file.name.clone(), file.bytes.as_ref()?.to_vec())))
+                .collect()
         });
+        self.enqueue_files(dropped);
 
         // Update preview when selection changes
         self.update_preview(ctx);
 
+        self.show_export_confirm(ctx);
+        self.show_rules_window(ctx);
+        self.show_tax_window(ctx);
+        self.show_large_preview(ctx);
+        self.show_ocr_text_window(ctx);
+        self.show_compare_window(ctx);
+        self.show_merge_dialog(ctx);
+
         // Top panel: title + controls
         egui::Panel::top("top_panel").show(ctx, |ui| {
             ui.add_space(4.0);
             ui.horizontal(|ui| {
                 ui.heading("카드 영수증 OCR");
+                // Running total, always in view without scrolling to the table footer
+                if !self.state.transactions.is_empty() {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let all = format!(
+                            "{}건 · {}원",
+                            self.state.transactions.len(),
+                            format_amount_grouped(
+                                self.state.total_amount_unfiltered(),
+                                self.state.digit_grouping
+                            )
+                        );
+                        let text = if self.state.filter.is_active() {
+                            format!(
+                                "필터: {}건 · {}원 / 전체: {}",
+                                self.state.filtered_indices().len(),
+                                format_amount_grouped(
+                                    self.state.total_amount(),
+                                    self.state.digit_grouping
+                                ),
+                                all
+                            )
+                        } else {
+                            all
+                        };
+                        let mut text = egui::RichText::new(text).strong();
+                        if self.state.budget_excess().is_some() {
+                            text = text.color(table::OVER_BUDGET_COLOR);
+                        }
+                        ui.label(text);
+                    });
+                }
             });
             ui.add_space(2.0);
 
-            ui.horizontal(|ui| {
-                // File upload button
-                if ui.button("이미지 업로드").clicked() {
+            ui.horizontal(|ui| {
+                // File upload button
+                if ui.button("이미지 업로드").clicked() {
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let file_queue = Arc::clone(&self.file_queue);
+                        spawn_local(async move {
+                            match ocr::pick_files().await {
+                                Ok(files) => {
+                                    let mut q = file_queue.lock().unwrap();
+                                    for (name, bytes) in files {
+                                        if is_image_file(&name) {
+                                            q.push((name, bytes));
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    log::error!("File picker error: {}", e);
+                                }
+                            }
+                        });
+                    }
+                }
+
+                // Rows from an earlier CSV export, routed through the file queue
+                if ui
+                    .button("CSV 가져오기")
+                    .on_hover_text("내보낸 CSV를 불러와 이어서 작업합니다 (이미지는 없음)")
+                    .clicked()
+                {
                     #[cfg(target_arch = "wasm32")]
                     {
                         let file_queue = Arc::clone(&self.file_queue);
                         spawn_local(async move {
-                            match ocr::pick_files().await {
+                            match ocr::pick_files_of_type(".csv,text/csv").await {
                                 Ok(files) => {
                                     let mut q = file_queue.lock().unwrap();
-                                    for (name, bytes) in files {
-                                        if is_image_file(&name) {
-                                            q.push((name, bytes));
-                                        }
-                                    }
+                                    q.extend(
+                                        files.into_iter().filter(|(name, _)| is_csv_file(name)),
+                                    );
                                 }
                                 Err(e) => {
                                     log::error!("File picker error: {}", e);
@@ -278,7 +1925,7 @@ impl eframe::App for CardReceiptApp {
                 let has_pending = !self.state.pending_images.is_empty();
                 if ui
                     .add_enabled(
-                        has_pending && !self.state.ocr_in_progress,
+                        has_pending && ocr_ready() && !self.state.ocr_in_progress,
                         egui::Button::new(format!(
                             "OCR 인식 시작 ({}개)",
                             self.state.pending_images.len()
@@ -289,6 +1936,8 @@ impl eframe::App for CardReceiptApp {
                     #[cfg(target_arch = "wasm32")]
                     self.process_pending_images(ctx);
                 }
+                ui.checkbox(&mut self.state.auto_start_ocr, "자동 인식")
+                    .on_hover_text("이미지를 추가하면 바로 OCR을 시작합니다");
 
                 // CSV export button
                 if ui
@@ -298,14 +1947,48 @@ impl eframe::App for CardReceiptApp {
                     )
                     .clicked()
                 {
-                    #[cfg(target_arch = "wasm32")]
-                    {
-                        let csv = self.state.to_csv();
-                        if let Err(e) = web_download::download_csv("카드사용내역.csv", &csv) {
-                            self.state.status_message = format!("CSV 다운로드 실패: {}", e);
-                        }
-                    }
+                    self.request_export(ExportKind::Csv);
+                }
+
+                // JSON export: same rows, times with a UTC offset
+                if ui
+                    .add_enabled(
+                        !self.state.transactions.is_empty(),
+                        egui::Button::new("JSON 내보내기"),
+                    )
+                    .clicked()
+                {
+                    self.request_export(ExportKind::Json);
+                }
+                ui.checkbox(&mut self.state.minimize_personal_info, "개인정보 최소화")
+                    .on_hover_text("JSON에서 OCR 원문과 카드번호를 뺍니다 (CSV에는 원래 없음)");
+
+                // Printable HTML table (Ctrl+P in the browser)
+                if ui
+                    .add_enabled(
+                        !self.state.transactions.is_empty(),
+                        egui::Button::new("HTML 내보내기"),
+                    )
+                    .on_hover_text("브라우저에서 열어 인쇄할 수 있는 표를 저장합니다")
+                    .clicked()
+                {
+                    self.request_export(ExportKind::Html);
                 }
+                ui.checkbox(&mut self.state.html_include_images, "이미지 포함")
+                    .on_hover_text("HTML에 영수증 썸네일을 넣습니다 (파일이 커짐)");
+                egui::ComboBox::from_id_salt("utc_offset")
+                    .selected_text(utc_offset_label(self.state.utc_offset_minutes))
+                    .show_ui(ui, |ui| {
+                        for (minutes, _) in UTC_OFFSETS {
+                            ui.selectable_value(
+                                &mut self.state.utc_offset_minutes,
+                                minutes,
+                                utc_offset_label(minutes),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text("JSON 날짜에 붙일 시간대 (서머타임 미반영, 표·CSV는 그대로)");
 
                 // ZIP bundle export: numbered images + CSV + PDF
                 if ui
@@ -315,37 +1998,246 @@ impl eframe::App for CardReceiptApp {
                     )
                     .clicked()
                 {
-                    #[cfg(target_arch = "wasm32")]
-                    {
-                        let csv = self.state.to_csv();
-                        let images: Vec<(&str, &[u8])> = self
-                            .state
-                            .transactions
-                            .iter()
-                            .map(|t| (t.filename.as_str(), t.image_bytes.as_slice()))
-                            .collect();
-                        match crate::pdf_export::generate_receipts_pdf(&self.state.transactions) {
-                            Ok(pdf_bytes) => {
-                                if let Err(e) = web_download::download_receipt_bundle(
-                                    &images,
-                                    csv.as_bytes(),
-                                    &pdf_bytes,
-                                    "영수증모음.zip",
-                                ) {
-                                    self.state.status_message = format!("ZIP 다운로드 실패: {}", e);
-                                }
-                            }
-                            Err(e) => {
-                                self.state.status_message = format!("PDF 생성 실패: {}", e);
+                    self.request_export(ExportKind::Zip);
+                }
+                egui::ComboBox::from_id_salt("csv_schema")
+                    .selected_text(format!("CSV: {}", self.state.csv_schema.name))
+                    .show_ui(ui, |ui| {
+                        for schema in CsvSchema::PRESETS {
+                            let headers: Vec<&str> =
+                                schema.columns.iter().map(|(_, h)| *h).collect();
+                            ui.selectable_value(&mut self.state.csv_schema, schema, schema.name)
+                                .on_hover_text(headers.join(", "));
+                        }
+                    })
+                    .response
+                    .on_hover_text("CSV 열 구성 (받는 도구에 맞게 선택)");
+                ui.label("금액:");
+                ui.radio_value(
+                    &mut self.state.csv_amount_format,
+                    CsvAmountFormat::Raw,
+                    "45000",
+                )
+                .on_hover_text("숫자 그대로 (엑셀에서 숫자로 인식)");
+                ui.radio_value(
+                    &mut self.state.csv_amount_format,
+                    CsvAmountFormat::Thousands,
+                    format_amount_grouped(45000, self.state.digit_grouping),
+                )
+                .on_hover_text("천 단위 구분 (따옴표로 감쌈)");
+                egui::ComboBox::from_id_salt("digit_grouping")
+                    .selected_text(format!(
+                        "구분: {}",
+                        format_amount_grouped(1000, self.state.digit_grouping)
+                    ))
+                    .show_ui(ui, |ui| {
+                        for grouping in DigitGrouping::ALL {
+                            ui.selectable_value(
+                                &mut self.state.digit_grouping,
+                                grouping,
+                                format_amount_grouped(45000, grouping),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text("표, 합계, HTML/PDF와 CSV(천 단위 선택 시)의 천 단위 구분 기호");
+                ui.checkbox(&mut self.state.csv_include_memo, "메모 열")
+                    .on_hover_text("CSV 마지막 열에 메모를 추가합니다");
+                ui.checkbox(&mut self.state.export_csv_by_category, "비용종류별 CSV")
+                    .on_hover_text("ZIP에 비용종류별 CSV를 by_category/ 폴더로 추가합니다");
+                ui.checkbox(&mut self.state.zip_keep_filenames, "원본 파일명")
+                    .on_hover_text("ZIP 안 이미지를 번호 대신 원래 파일명으로 저장합니다");
+
+                // PDF of checked rows (all rows when multi-select is off)
+                let pdf_enabled = if self.state.multi_select {
+                    !self.state.selected_rows.is_empty()
+                } else {
+                    !self.state.transactions.is_empty()
+                };
+                egui::ComboBox::from_id_salt("pdf_layout")
+                    .selected_text(match self.state.pdf_layout {
+                        PdfLayout::OnePerPage => "PDF: 전체 이미지",
+                        PdfLayout::Summary => "PDF: 요약+이미지",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.state.pdf_layout,
+                            PdfLayout::OnePerPage,
+                            "전체 이미지",
+                        );
+                        ui.selectable_value(
+                            &mut self.state.pdf_layout,
+                            PdfLayout::Summary,
+                            "요약+이미지",
+                        );
+                    });
+                ui.checkbox(&mut self.state.pdf_include_filename, "파일명")
+                    .on_hover_text("PDF 각 쪽에 원본 이미지 파일명을 표시합니다");
+                if ui
+                    .add_enabled(pdf_enabled, egui::Button::new("선택 PDF"))
+                    .on_hover_text("다중 선택 시 선택한 행만, 아니면 전체를 PDF로 내보냅니다")
+                    .clicked()
+                {
+                    self.request_export(ExportKind::SelectedPdf);
+                }
+                if ui
+                    .add_enabled(pdf_enabled, egui::Button::new("선택 행 CSV 복사"))
+                    .on_hover_text(
+                        "다중 선택 시 선택한 행만, 아니면 전체를 CSV로 클립보드에 복사합니다",
+                    )
+                    .clicked()
+                {
+                    let rows = self.state.export_selection().len();
+                    ctx.copy_text(self.state.selection_to_csv());
+                    self.state.status_message = format!("{}건을 CSV로 복사했습니다", rows);
+                }
+
+                if ui
+                    .checkbox(&mut self.state.multi_select, "다중 선택")
+                    .changed()
+                    && !self.state.multi_select
+                {
+                    self.state.selected_rows.clear();
+                }
+                if self.state.multi_select
+                    && ui
+                        .add_enabled(
+                            self.state.selected_rows.len() == 2,
+                            egui::Button::new("비교"),
+                        )
+                        .on_hover_text("체크한 두 거래의 영수증을 나란히 봅니다")
+                        .clicked()
+                {
+                    self.open_compare(ctx);
+                }
+                if self.state.multi_select
+                    && ui
+                        .add_enabled(
+                            self.state.selected_rows.len() == 2,
+                            egui::Button::new("병합"),
+                        )
+                        .on_hover_text("체크한 두 거래를 하나로 합칩니다")
+                        .clicked()
+                {
+                    let rows: Vec<usize> = self.state.selected_rows.iter().copied().collect();
+                    if let [a, b] = rows[..] {
+                        let ids = [self.state.transactions[a].id, self.state.transactions[b].id];
+                        self.merge_dialog = Some((ids, MergeChoices::default()));
+                    }
+                }
+
+                ui.checkbox(&mut self.state.auto_rotate, "자동 회전")
+                    .on_hover_text("OCR 전에 글자 방향을 감지해 이미지를 회전합니다 (느려짐)");
+
+                ui.label("동시 OCR");
+                ui.add(egui::DragValue::new(&mut self.state.ocr_concurrency).range(1..=16))
+                    .on_hover_text("한 번에 실행할 OCR 작업 수 (많을수록 빠르지만 불안정)");
+
+                ui.label("금액 상한");
+                ui.add(
+                    egui::DragValue::new(&mut self.state.amount_ceiling)
+                        .range(1_000_000..=i64::MAX / 2)
+                        .speed(1_000_000)
+                        .suffix("원"),
+                )
+                .on_hover_text(
+                    "인식한 금액이 이보다 크면 오인식으로 보고 0원과 확인 필요로 표시합니다",
+                );
+
+                ui.label("예산");
+                if range_input(ui, &mut self.budget_input, "없음", |t| {
+                    parse_amount_input(t).is_some()
+                }) {
+                    self.state.budget_limit =
+                        parse_amount_input(&self.budget_input).filter(|&v| v > 0);
+                }
+
+                if !self.state.is_default_sort()
+                    && ui
+                        .button("정렬 초기화")
+                        .on_hover_text("업로드 순서로 되돌립니다")
+                        .clicked()
+                {
+                    self.state.reset_sort();
+                }
+
+                ui.menu_button("열 설정", |ui| {
+                    ui.checkbox(&mut self.state.show_seconds, "시각에 초 표시")
+                        .on_hover_text("표와 CSV 시간 열에 초까지 표시합니다");
+                    ui.separator();
+                    for column in OptionalColumn::ALL {
+                        let mut shown = self.state.visible_columns.contains(&column);
+                        if ui.checkbox(&mut shown, column.label()).changed() {
+                            if shown {
+                                self.state.visible_columns.insert(column);
+                            } else {
+                                self.state.visible_columns.remove(&column);
                             }
                         }
                     }
+                })
+                .response
+                .on_hover_text("표에 보일 열을 고릅니다 (#, 날짜, 가맹점, 금액은 항상 표시)");
+
+                ui.checkbox(&mut self.state.group_by_expense, "비용종류별 소계")
+                    .on_hover_text("비용종류별로 묶고 그룹마다 소계 행을 표시합니다");
+
+                if !self.state.failed_images.is_empty() {
+                    ui.checkbox(
+                        &mut self.state.include_failed_images,
+                        format!("인식 실패 {}장 포함", self.state.failed_images.len()),
+                    )
+                    .on_hover_text("OCR에 실패한 이미지도 PDF/ZIP 끝에 붙입니다 (수기 입력용)");
+                }
+
+                ui.checkbox(&mut self.state.exclude_cancelled, "취소 제외")
+                    .on_hover_text("승인취소된 거래를 합계와 내보내기에서 뺍니다");
+
+                ui.checkbox(&mut self.state.hide_zero_amount, "0원 숨기기")
+                    .on_hover_text("금액이 0원인 행을 표에서 숨깁니다 (삭제하지 않음)");
+                if self.state.hide_zero_amount {
+                    ui.checkbox(&mut self.state.exclude_zero_from_export, "내보내기 제외")
+                        .on_hover_text("숨긴 0원 행을 CSV/ZIP/PDF에서도 뺍니다");
+                }
+
+                ui.checkbox(&mut self.state.auto_apply_expense, "자동 적용")
+                    .on_hover_text("새로 인식된 거래에 추천 비용종류를 자동으로 지정합니다");
+                if ui.button("규칙 관리").clicked() {
+                    self.rules_window_open = !self.rules_window_open;
+                }
+                if ui
+                    .button("부가세 요약")
+                    .on_hover_text("공급가액/부가세/합계를 세금계산서 양식으로 봅니다")
+                    .clicked()
+                {
+                    self.tax_window_open = !self.tax_window_open;
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.state.undo_stack.is_empty(),
+                        egui::Button::new("↶ 실행 취소"),
+                    )
+                    .on_hover_text("마지막 수정을 되돌립니다 (Ctrl+Z)")
+                    .clicked()
+                {
+                    self.undo();
                 }
 
                 // Clear button
                 if ui.button("초기화").clicked() {
-                    self.state = AppState::new();
+                    // Drop images still waiting for a slot; running tasks finish on their own
+                    let queued = self.ocr_queue.len();
+                    self.ocr_queue.clear();
+                    {
+                        let mut remaining = self.ocr_remaining.lock().unwrap();
+                        *remaining = remaining.saturating_sub(queued);
+                    }
+                    self.state.reset();
+                    self.filter_inputs = FilterInputs::default();
                     self.preview_texture = None;
+                    self.preview_cache.clear();
+                    self.ocr_cache.lock().unwrap().clear();
                     self.preview_loaded_for = None;
                 }
             });
@@ -355,20 +2247,57 @@ impl eframe::App for CardReceiptApp {
                 if self.state.ocr_in_progress {
                     ui.spinner();
                 }
-                ui.label(&self.state.status_message);
+                if !ocr_ready() {
+                    // The spinner keeps repainting, so the label clears once the worker is up
+                    ui.spinner();
+                    ui.label("OCR 엔진 로딩 중...");
+                } else {
+                    ui.label(&self.state.status_message);
+                }
 
                 if !self.state.pending_images.is_empty() && !self.state.ocr_in_progress {
                     ui.label(format!("| 대기 중: {}개", self.state.pending_images.len()));
                 }
+
+                let loaded = self.loaded_image_bytes();
+                if loaded > IMAGE_BYTES_WARNING {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(230, 160, 40),
+                        format!(
+                            "⚠ 이미지 {}MB 사용 중 — 나눠서 처리하는 것을 권장합니다",
+                            loaded / (1024 * 1024)
+                        ),
+                    )
+                    .on_hover_text(format!(
+                        "{}MB를 넘으면 새 업로드를 받지 않습니다",
+                        IMAGE_BYTES_LIMIT / (1024 * 1024)
+                    ));
+                }
             });
             ui.add_space(2.0);
         });
 
+        // Totals pinned under the table, spanning the side panels too
+        if !self.state.transactions.is_empty() {
+            egui::Panel::bottom("table_footer").show(ctx, |ui| {
+                ui.add_space(2.0);
+                table::render_table_footer(ui, &self.state);
+                ui.add_space(2.0);
+            });
+        }
+
         // [테이블] [수정 칸] [미리보기] 3칼럼 레이아웃
         // Side panels must be added before CentralPanel
-        if let Some(idx) = self.state.selected_index {
+        if let Some(idx) = self.state.selected_index() {
             let mut close_panel = false;
             let mut save_edits = false;
+            let mut duplicate = false;
+            let mut merchant_changed = false;
+            let mut tag_added: Option<String> = None;
+            let mut tag_removed: Option<String> = None;
+            let mut attach = false;
+            let mut detach: Option<usize> = None;
+            let extra_previews = self.extra_previews(ctx, idx);
 
             // Rightmost: image preview (scrollable for tall phone screenshots)
             egui::Panel::right("image_preview")
@@ -382,22 +2311,90 @@ impl eframe::App for CardReceiptApp {
                             if ui.button("✕").clicked() {
                                 close_panel = true;
                             }
+                            if ui
+                                .add_enabled(
+                                    matches!(self.preview_texture, Some(Ok(_))),
+                                    egui::Button::new("크게 보기"),
+                                )
+                                .clicked()
+                            {
+                                self.large_preview_open = true;
+                                self.large_preview_zoom = 1.0;
+                            }
+                            ui.checkbox(&mut self.state.show_ocr_highlights, "인식 위치")
+                                .on_hover_text(
+                                    "날짜(파랑)·가맹점(초록)·금액(주황)을 읽은 위치 표시",
+                                );
                         });
                     });
                     ui.separator();
 
+                    // Supporting images: hover for a larger view
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("첨부");
+                        for (k, preview) in extra_previews.iter().enumerate() {
+                            match preview {
+                                Ok(texture) => {
+                                    let [tw, th] = texture.size();
+                                    let size = egui::vec2(tw as f32, th as f32);
+                                    ui.add(egui::Image::new((texture.id(), size)).max_height(48.0))
+                                        .on_hover_ui(|ui| {
+                                            ui.add(
+                                                egui::Image::new((texture.id(), size))
+                                                    .max_width(360.0),
+                                            );
+                                        });
+                                }
+                                Err(reason) => {
+                                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "⚠")
+                                        .on_hover_text(reason);
+                                }
+                            }
+                            if ui.small_button("✕").on_hover_text("첨부 삭제").clicked() {
+                                detach = Some(k);
+                            }
+                        }
+                        if ui
+                            .small_button("+ 이미지 첨부")
+                            .on_hover_text("상세 영수증 등 추가 증빙 이미지를 붙입니다 (PDF에 3a, 3b쪽으로 포함)")
+                            .clicked()
+                        {
+                            attach = true;
+                        }
+                    });
+                    ui.separator();
+
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        if let Some(texture) = &self.preview_texture {
+                        let restored = self
+                            .state
+                            .transactions
+                            .get(idx)
+                            .is_some_and(|t| t.image_bytes.is_empty());
+                        if restored {
+                            // Images are not persisted across sessions, so this is expected
+                            ui.colored_label(
+                                egui::Color32::GRAY,
+                                "이전 세션에서 복원된 거래입니다\n(원본 이미지는 저장되지 않음)",
+                            );
+                        } else if let Some(Err(reason)) = &self.preview_texture {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 100, 100),
+                                format!("이미지를 불러올 수 없습니다\n{}", reason),
+                            );
+                        } else if let Some(Ok(texture)) = &self.preview_texture {
                             let available_width = ui.available_width();
                             let [tw, th] = texture.size();
                             let scale = available_width / tw as f32;
                             let display_height = th as f32 * scale;
-                            ui.image(egui::load::SizedTexture::new(
+                            let response = ui.image(egui::load::SizedTexture::new(
                                 texture.id(),
                                 egui::vec2(available_width, display_height),
                             ));
-                        } else {
-                            ui.colored_label(egui::Color32::GRAY, "이미지를 불러올 수 없습니다");
+                            if self.state.show_ocr_highlights
+                                && let Some(txn) = self.state.transactions.get(idx)
+                            {
+                                paint_field_highlights(ui, response.rect, txn);
+                            }
                         }
                     });
                 });
@@ -412,16 +2409,31 @@ impl eframe::App for CardReceiptApp {
                     ui.separator();
                     ui.add_space(4.0);
 
+                    // Fields the parser could not find, so the user knows what to fill in
+                    if let Some(txn) = self.state.transactions.get(idx)
+                        && txn.needs_review
+                    {
+                        for note in &txn.review_notes {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 180, 80),
+                                format!("⚠ {}", note),
+                            );
+                        }
+                        ui.add_space(4.0);
+                    }
+
                     egui::Grid::new("edit_grid")
                         .num_columns(2)
                         .spacing([10.0, 0.0])
                         .striped(true)
                         .show(ui, |ui| {
                             ui.label("가맹점");
-                            ui.add(
+                            let merchant_resp = ui.add(
                                 egui::TextEdit::singleline(&mut self.edit_merchant)
                                     .desired_width(f32::INFINITY),
                             );
+                            merchant_changed = merchant_resp.changed();
+                            self.show_merchant_autocomplete(ui, &merchant_resp);
                             ui.end_row();
 
                             ui.label("금액");
@@ -431,6 +2443,88 @@ impl eframe::App for CardReceiptApp {
                             );
                             ui.end_row();
 
+                            // Written-out amount for formal documents (지출결의서 등)
+                            if let Some(amount) = parse_amount_input(&self.edit_amount_str) {
+                                let text = table::krw_to_korean_text(amount.unsigned_abs());
+                                ui.label("");
+                                ui.horizontal(|ui| {
+                                    ui.weak(&text);
+                                    if ui
+                                        .small_button("📋")
+                                        .on_hover_text("한글 금액 복사")
+                                        .clicked()
+                                    {
+                                        ui.ctx().copy_text(text.clone());
+                                    }
+                                });
+                                ui.end_row();
+
+                                // Soft typo check against the saved and the OCR-read amount
+                                let txn = &self.state.transactions[idx];
+                                let far_from = [
+                                    ("저장된 금액", Some(txn.amount)),
+                                    ("원래 인식값", txn.parsed_amount),
+                                ]
+                                .into_iter()
+                                .find_map(|(what, reference)| {
+                                    let reference = reference.filter(|r| *r != 0)?;
+                                    amounts_far_apart(amount, reference)
+                                        .then_some((what, reference))
+                                });
+                                if let Some((what, reference)) = far_from {
+                                    ui.label("");
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(255, 180, 80),
+                                        format!(
+                                            "⚠ {} {}원과 크게 다릅니다",
+                                            what,
+                                            format_amount_grouped(
+                                                reference,
+                                                self.state.digit_grouping
+                                            )
+                                        ),
+                                    );
+                                    ui.end_row();
+                                }
+                            }
+
+                            // Pick the authoritative amount when OCR misread the total
+                            // but got the 공급가액/부가세 breakdown right
+                            let txn = &self.state.transactions[idx];
+                            if let (Some(supply), Some(vat)) = (txn.supply_amount, txn.vat_amount) {
+                                let sum = supply + vat;
+                                ui.label("");
+                                ui.vertical(|ui| {
+                                    ui.weak(format!(
+                                        "공급가액 {} + 부가세 {} = {}원",
+                                        format_amount_grouped(supply, self.state.digit_grouping),
+                                        format_amount_grouped(vat, self.state.digit_grouping),
+                                        format_amount_grouped(sum, self.state.digit_grouping)
+                                    ));
+                                    if ui
+                                        .small_button("합계 사용")
+                                        .on_hover_text("공급가액 + 부가세로 금액을 바꿉니다")
+                                        .clicked()
+                                    {
+                                        self.edit_amount_str = format_amount(sum);
+                                        save_edits = true;
+                                    }
+                                    if sum != txn.amount {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(255, 180, 80),
+                                            format!(
+                                                "⚠ 현재 금액 {}원과 다릅니다",
+                                                format_amount_grouped(
+                                                    txn.amount,
+                                                    self.state.digit_grouping
+                                                )
+                                            ),
+                                        );
+                                    }
+                                });
+                                ui.end_row();
+                            }
+
                             ui.label("날짜");
                             ui.add(
                                 egui::TextEdit::singleline(&mut self.edit_datetime_str)
@@ -440,30 +2534,168 @@ impl eframe::App for CardReceiptApp {
 
                             // Expense type field
                             ui.label("비용종류");
-                            ui.add(
+                            let expense_resp = ui.add(
                                 egui::TextEdit::singleline(&mut self.edit_expense_type)
                                     .desired_width(f32::INFINITY),
                             );
+                            if expense_resp.changed() {
+                                self.edit_expense_auto = false;
+                            }
                             ui.end_row();
                         });
 
                     ui.add_space(4.0);
 
-                    // Expense recommendation from keyword matching
-                    let recommendation = expense::detect_expense(&self.edit_merchant);
-                    if let Some(rec) = &recommendation {
+                    // Expense recommendations from keyword matching, best first
+                    let recommendations = self.state.expense_rules.detect_all(&self.edit_merchant);
+                    let recommendation = recommendations.first();
+
+                    // A different rule now matches: drop the stale auto-applied type,
+                    // but never touch a type the user chose
+                    if merchant_changed
+                        && self.edit_expense_auto
+                        && !self.edit_expense_type.is_empty()
+                        && recommendation.map(|r| r.label.as_str())
+                            != Some(self.edit_expense_type.as_str())
+                    {
+                        self.edit_expense_type.clear();
+                    }
+
+                    if let Some(rec) = recommendation {
+                        // Pulse the apply button while the expense field is still empty
+                        let nudge = self.edit_expense_type.is_empty();
                         ui.horizontal(|ui| {
                             ui.colored_label(
                                 egui::Color32::from_rgb(100, 180, 255),
                                 format!("추천: {}", rec.label),
                             );
-                            if ui.button("적용").clicked() {
+                            let mut button = egui::Button::new("적용");
+                            if nudge {
+                                let t = ui.input(|i| i.time);
+                                let pulse = ((t * 3.0).sin() * 0.5 + 0.5) as f32;
+                                let alpha = (60.0 + 120.0 * pulse) as u8;
+                                button = button.fill(egui::Color32::from_rgba_unmultiplied(
+                                    100, 180, 255, alpha,
+                                ));
+                                ui.ctx().request_repaint();
+                            }
+                            if ui.add(button).clicked() {
                                 self.edit_expense_type = rec.label.clone();
+                                self.edit_expense_auto = false;
                                 save_edits = true;
                             }
+                            // Other rules that also match, for merchants that fit several types
+                            if recommendations.len() > 1 {
+                                egui::ComboBox::from_id_salt("other_recommendations")
+                                    .selected_text(format!("외 {}개", recommendations.len() - 1))
+                                    .show_ui(ui, |ui| {
+                                        for other in recommendations
+                                            .iter()
+                                            .skip(1)
+                                            .take(MAX_EXTRA_RECOMMENDATIONS)
+                                        {
+                                            if ui
+                                                .selectable_label(
+                                                    self.edit_expense_type == other.label,
+                                                    &other.label,
+                                                )
+                                                .on_hover_text(&other.category)
+                                                .clicked()
+                                            {
+                                                self.edit_expense_type = other.label.clone();
+                                                self.edit_expense_auto = false;
+                                                save_edits = true;
+                                            }
+                                        }
+                                    });
+                            }
                         });
                     }
 
+                    // Help verify an ambiguous OCR'd merchant name
+                    if ui
+                        .add_enabled(
+                            !self.edit_merchant.trim().is_empty(),
+                            egui::Button::new("가맹점 검색"),
+                        )
+                        .on_hover_text(MERCHANT_SEARCH_HINT)
+                        .clicked()
+                    {
+                        search_merchant(ui.ctx(), self.edit_merchant.trim());
+                    }
+
+                    ui.add_space(4.0);
+                    ui.label("메모");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.edit_memo)
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("예: 고객 접대 - 김부장"),
+                    );
+
+                    ui.add_space(4.0);
+                    ui.label("태그");
+                    ui.horizontal_wrapped(|ui| {
+                        for tag in &self.state.transactions[idx].tags {
+                            if ui
+                                .small_button(format!("#{} ✕", tag))
+                                .on_hover_text("태그 제거")
+                                .clicked()
+                            {
+                                tag_removed = Some(tag.clone());
+                            }
+                        }
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.edit_tag_input)
+                                .desired_width(90.0)
+                                .hint_text("예: 출장"),
+                        );
+                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            tag_added = Some(std::mem::take(&mut self.edit_tag_input));
+                            response.request_focus();
+                        }
+                    });
+
+                    // Read-only details picked up by OCR
+                    let txn = &self.state.transactions[idx];
+                    if txn.card_number.is_some()
+                        || txn.approval_number.is_some()
+                        || txn.business_number.is_some()
+                    {
+                        ui.add_space(4.0);
+                        egui::CollapsingHeader::new("거래 상세")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                egui::Grid::new("txn_details")
+                                    .num_columns(2)
+                                    .show(ui, |ui| {
+                                        if let Some(card) = &txn.card_number {
+                                            ui.label("카드번호");
+                                            ui.monospace(card);
+                                            ui.end_row();
+                                        }
+                                        if let Some(approval) = &txn.approval_number {
+                                            ui.label("승인번호");
+                                            ui.monospace(approval);
+                                            ui.end_row();
+                                        }
+                                        if let Some(number) = &txn.business_number {
+                                            ui.label("사업자번호");
+                                            ui.horizontal(|ui| {
+                                                ui.monospace(number);
+                                                if !parser::is_valid_business_number(number) {
+                                                    ui.colored_label(egui::Color32::GRAY, "?")
+                                                        .on_hover_text(
+                                                            "검증 숫자가 맞지 않습니다 (오인식일 수 있음)",
+                                                        );
+                                                }
+                                            });
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    }
+
                     // Quick-select buttons for common expense types
                     ui.add_space(4.0);
                     ui.label("빠른 선택:");
@@ -471,6 +2703,7 @@ impl eframe::App for CardReceiptApp {
                         for label in expense::all_expense_labels() {
                             if ui.small_button(*label).clicked() {
                                 self.edit_expense_type = label.to_string();
+                                self.edit_expense_auto = false;
                                 save_edits = true;
                             }
                         }
@@ -478,10 +2711,31 @@ impl eframe::App for CardReceiptApp {
 
                     ui.add_space(8.0);
 
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("이미지 교체")
+                            .on_hover_text("잘못 올린 이미지를 다른 파일로 바꿉니다")
+                            .clicked()
+                        {
+                            self.pick_row_images(idx, RowImageAction::Replace);
+                        }
+                        ui.checkbox(&mut self.state.rerun_ocr_on_replace, "다시 인식")
+                            .on_hover_text("교체한 이미지로 날짜·가맹점·금액을 다시 읽습니다");
+                    });
+
                     ui.horizontal(|ui| {
                         if ui.button("저장").clicked() {
                             save_edits = true;
                         }
+                        if ui
+                            .button("복제")
+                            .on_hover_text(
+                                "이 거래를 복사해 아래에 추가합니다 (날짜만 바꿔 반복 지출 입력)",
+                            )
+                            .clicked()
+                        {
+                            duplicate = true;
+                        }
                         if ui.button("닫기").clicked() {
                             close_panel = true;
                         }
@@ -492,8 +2746,24 @@ impl eframe::App for CardReceiptApp {
                 self.apply_edits(idx);
                 self.preview_loaded_for = None;
             }
+            if attach {
+                self.pick_row_images(idx, RowImageAction::Attach);
+            }
+            if let Some(k) = detach {
+                self.state.remove_extra_image(idx, k);
+            }
+            if let Some(tag) = tag_added {
+                self.state.add_tag(idx, &tag);
+            }
+            if let Some(tag) = tag_removed {
+                self.state.remove_tag(idx, &tag);
+            }
+            if duplicate {
+                // The copy becomes the selected row; the panel reloads with its fields
+                self.state.duplicate(idx);
+            }
             if close_panel {
-                self.state.selected_index = None;
+                self.state.selected_id = None;
                 self.preview_loaded_for = None;
                 self.preview_texture = None;
             }
@@ -501,20 +2771,25 @@ impl eframe::App for CardReceiptApp {
 
         // Central panel: transaction table or empty state
         egui::CentralPanel::default().show(ctx, |ui| {
-            if self.state.transactions.is_empty() && !self.state.ocr_in_progress {
-                ui.centered_and_justified(|ui| {
-                    ui.label(
-                        egui::RichText::new(
-                            "이미지를 여기에 드래그하거나\n위의 '이미지 업로드' 버튼을 클릭하세요",
-                        )
-                        .size(18.0)
-                        .color(egui::Color32::GRAY),
-                    );
-                });
+            if self.state.transactions.is_empty()
+                && !self.state.ocr_in_progress
+                && !self.state.pending_images.is_empty()
+            {
+                self.show_pending_thumbnails(ui);
+            } else if self.state.transactions.is_empty() && !self.state.ocr_in_progress {
+                let hovering = ui.input(|i| !i.raw.hovered_files.is_empty());
+                drop_zone(ui, hovering);
             } else {
-                table::render_transaction_table(ui, &mut self.state);
+                self.show_month_tabs(ui);
+                self.show_filter_bar(ui);
+                if table::render_transaction_table(ui, &mut self.state) {
+                    // A row changed (inline edit or row menu); reload the side panel fields
+                    self.preview_loaded_for = None;
+                }
             }
 
+            self.show_review_section(ui);
+
             // Error messages at the bottom
             if !self.state.error_messages.is_empty() {
                 ui.separator();
@@ -525,32 +2800,329 @@ impl eframe::App for CardReceiptApp {
                 });
             }
         });
+
+        // Draw one more frame so the queued export runs right after its status shows
+        if self.queued_pdf_export.is_some() {
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// Translucent boxes over the preview for the OCR words each parsed field came from
+fn paint_field_highlights(ui: &egui::Ui, image_rect: egui::Rect, txn: &CardTransaction) {
+    let Some(layout) = &txn.ocr_layout else {
+        return;
+    };
+    if layout.width <= 0.0 || layout.height <= 0.0 {
+        return;
+    }
+    let sx = image_rect.width() / layout.width;
+    let sy = image_rect.height() / layout.height;
+    let painter = ui.painter_at(image_rect);
+
+    let groups = [
+        (
+            &txn.field_words.datetime,
+            egui::Color32::from_rgb(100, 180, 255),
+        ),
+        (
+            &txn.field_words.merchant,
+            egui::Color32::from_rgb(100, 200, 100),
+        ),
+        (
+            &txn.field_words.amount,
+            egui::Color32::from_rgb(255, 180, 80),
+        ),
+    ];
+    for (indices, color) in groups {
+        for word in indices.iter().filter_map(|&i| layout.words.get(i)) {
+            let [x0, y0, x1, y1] = word.bbox;
+            let rect = egui::Rect::from_min_max(
+                image_rect.min + egui::vec2(x0 * sx, y0 * sy),
+                image_rect.min + egui::vec2(x1 * sx, y1 * sy),
+            );
+            painter.rect_filled(rect, 2.0, color.gamma_multiply(0.3));
+            painter.rect_stroke(
+                rect,
+                2.0,
+                egui::Stroke::new(1.5, color),
+                egui::StrokeKind::Outside,
+            );
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const MERCHANT_SEARCH_HINT: &str = "가맹점명을 복사하고 지도에서 검색합니다";
+#[cfg(target_arch = "wasm32")]
+const MERCHANT_SEARCH_HINT: &str = "가맹점명을 클립보드에 복사합니다";
+
+/// Copy the merchant name; on desktop also open a map search for it
+fn search_merchant(ctx: &egui::Context, merchant: &str) {
+    ctx.copy_text(merchant.to_string());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let url = format!("https://map.naver.com/p/search/{}", url_encode(merchant));
+        if let Err(e) = open::that(&url) {
+            log::warn!("Failed to open {}: {}", url, e);
+        }
+    }
+}
+
+/// Percent-encode a path segment (UTF-8 bytes outside the unreserved set)
+#[cfg(not(target_arch = "wasm32"))]
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+/// OCR and parse one image. Parsing never drops the image: partial results
+/// become review rows, and only an OCR failure gives a `FailedImage`.
+#[cfg(target_arch = "wasm32")]
+async fn recognize_image(
+    image: PendingImage,
+    auto_rotate: bool,
+    cache: &Mutex<OcrCache>,
+    noise_tokens: &[String],
+    amount_ceiling: i64,
+    grouping: DigitGrouping,
+) -> OcrResult {
+    let PendingImage {
+        filename,
+        bytes,
+        adjust,
+    } = image;
+    // Read before rotating: a re-encoded image has no EXIF
+    let capture_time = preprocess::exif_datetime(&bytes);
+    // Keyed on the upload itself, so a hit also skips rotation and adjustment
+    let key = ocr_cache_key(&bytes, &adjust, auto_rotate);
+    let cached = {
+        let mut cache = cache.lock().unwrap();
+        cache.iter().position(|(k, _)| *k == key).map(|pos| {
+            let entry = cache.remove(pos).unwrap();
+            let hit = entry.1.clone();
+            cache.push_back(entry);
+            hit
+        })
+    };
+    let recognized = match cached {
+        Some(hit) => {
+            log::info!("OCR cache hit: {}", filename);
+            Ok(hit)
+        }
+        None => {
+            // Rotated bytes are kept so the preview and PDF are upright too
+            let bytes = if auto_rotate {
+                ocr::auto_rotate(bytes).await
+            } else {
+                bytes
+            };
+
+            // Adjustments only feed OCR; the stored image stays as uploaded
+            let adjusted = if adjust.is_identity() {
+                None
+            } else {
+                preprocess::adjust_bytes(&bytes, &adjust)
+                    .inspect_err(|e| log::warn!("Image adjustment failed: {}", e))
+                    .ok()
+            };
+            let ocr_input = adjusted.as_deref().unwrap_or(&bytes);
+
+            match ocr::recognize_words(ocr_input).await {
+                Ok((text, layout)) => {
+                    let hit = (Arc::from(bytes), text, layout);
+                    let mut cache = cache.lock().unwrap();
+                    if cache.len() >= OCR_CACHE_SIZE {
+                        cache.pop_front();
+                    }
+                    cache.push_back((key, hit.clone()));
+                    Ok(hit)
+                }
+                Err(e) => Err((bytes, e)),
+            }
+        }
+    };
+
+    match recognized {
+        Ok((bytes, text, layout)) => {
+            // Two-column receipts come out of Tesseract row by row across both columns
+            let text = if parser::column_gutter(&layout.lines).is_some() {
+                parser::reflow_lines(&layout.lines)
+            } else {
+                text
+            };
+            let mut txn = parser::parse_receipt(
+                &filename,
+                &text,
+                noise_tokens,
+                capture_time,
+                amount_ceiling,
+                grouping,
+                Some(layout),
+            );
+            txn.image_bytes = bytes;
+            Ok(txn)
+        }
+        Err((bytes, e)) => Err(FailedImage {
+            filename,
+            bytes,
+            reason: format!("OCR 실패: {}", e),
+        }),
     }
 }
 
+/// OCR cache key: the uploaded bytes plus the settings that change what OCR sees
+#[cfg(target_arch = "wasm32")]
+fn ocr_cache_key(bytes: &[u8], adjust: &ImageAdjust, auto_rotate: bool) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    adjust.brightness.hash(&mut hasher);
+    adjust.contrast.to_bits().hash(&mut hasher);
+    adjust.threshold.hash(&mut hasher);
+    auto_rotate.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of image contents, used as the preview cache key
+fn content_hash(bytes: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn is_image_file(name: &str) -> bool {
     let lower = name.to_lowercase();
     lower.ends_with(".jpg") || lower.ends_with(".jpeg") || lower.ends_with(".png")
 }
 
+fn is_csv_file(name: &str) -> bool {
+    name.to_lowercase().ends_with(".csv")
+}
+
+/// Whether the bytes really are a JPEG or PNG (checked by signature, since a
+/// renamed PDF or HEIC passes `is_image_file`)
+fn has_image_signature(bytes: &[u8]) -> bool {
+    matches!(
+        image::guess_format(bytes),
+        Ok(image::ImageFormat::Jpeg | image::ImageFormat::Png)
+    )
+}
+
+/// Decode image bytes into a texture, downscaled so the longest side is at most `max_side`.
+/// Errors describe why (no data, unsupported format, corrupt data) for display.
 fn decode_image_to_texture(
     ctx: &egui::Context,
     name: &str,
     bytes: &[u8],
-) -> Option<egui::TextureHandle> {
+    max_side: u32,
+) -> Result<egui::TextureHandle, String> {
+    let img = decode_image(bytes, max_side)?;
+    Ok(image_to_texture(ctx, name, img))
+}
+
+/// Decode image bytes, downscaled so the longest side is at most `max_side`
+fn decode_image(bytes: &[u8], max_side: u32) -> Result<image::DynamicImage, String> {
     if bytes.is_empty() {
-        return None;
+        return Err("이미지 데이터가 없습니다".into());
     }
-    let img = image::load_from_memory(bytes).ok()?;
-    // Resize if too large for preview (max 1024px on longest side)
-    let img = if img.width() > 1024 || img.height() > 1024 {
-        img.resize(1024, 1024, image::imageops::FilterType::Triangle)
+    let img = image::load_from_memory(bytes).map_err(|e| match e {
+        image::ImageError::Unsupported(_) => format!("지원하지 않는 이미지 형식입니다 ({})", e),
+        _ => format!("이미지가 손상되었거나 디코딩에 실패했습니다 ({})", e),
+    })?;
+    Ok(if img.width() > max_side || img.height() > max_side {
+        img.resize(max_side, max_side, image::imageops::FilterType::Triangle)
     } else {
         img
-    };
+    })
+}
+
+fn image_to_texture(
+    ctx: &egui::Context,
+    name: &str,
+    img: image::DynamicImage,
+) -> egui::TextureHandle {
     let rgba = img.to_rgba8();
     let size = [rgba.width() as usize, rgba.height() as usize];
     let pixels = rgba.into_raw();
     let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
-    Some(ctx.load_texture(name, color_image, egui::TextureOptions::LINEAR))
+    ctx.load_texture(name, color_image, egui::TextureOptions::LINEAR)
+}
+
+/// Dashed drop target filling the empty central panel, highlighted while files are dragged over
+fn drop_zone(ui: &mut egui::Ui, hovering: bool) {
+    let rect = ui.available_rect_before_wrap().shrink(16.0);
+    ui.allocate_rect(rect, egui::Sense::hover());
+    let (color, fill) = if hovering {
+        let accent = ui.visuals().selection.stroke.color;
+        (accent, accent.gamma_multiply(0.08))
+    } else {
+        (egui::Color32::GRAY, egui::Color32::TRANSPARENT)
+    };
+
+    let painter = ui.painter();
+    painter.rect_filled(rect, 8.0, fill);
+    let corners = [
+        rect.left_top(),
+        rect.right_top(),
+        rect.right_bottom(),
+        rect.left_bottom(),
+        rect.left_top(),
+    ];
+    painter.extend(egui::Shape::dashed_line(
+        &corners,
+        egui::Stroke::new(if hovering { 2.0 } else { 1.0 }, color),
+        10.0,
+        6.0,
+    ));
+    let text = if hovering {
+        "놓으면 이미지가 추가됩니다"
+    } else {
+        "이미지를 여기에 드래그하거나\n위의 '이미지 업로드' 버튼을 클릭하세요"
+    };
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::proportional(18.0),
+        color,
+    );
+}
+
+/// Brightness/contrast/threshold sliders under a pending thumbnail; true if changed
+fn adjust_controls(ui: &mut egui::Ui, id: usize, adjust: &mut ImageAdjust) -> bool {
+    let before = *adjust;
+    egui::CollapsingHeader::new(egui::RichText::new("이미지 조정").small())
+        .id_salt(("image_adjust", id))
+        .show(ui, |ui| {
+            ui.spacing_mut().slider_width = 80.0;
+            ui.add(egui::Slider::new(&mut adjust.brightness, -100..=100).text("밝기"));
+            ui.add(egui::Slider::new(&mut adjust.contrast, -50.0..=100.0).text("대비"));
+            let mut binarize = adjust.threshold.is_some();
+            ui.checkbox(&mut binarize, "흑백 변환");
+            if binarize {
+                let threshold = adjust
+                    .threshold
+                    .get_or_insert(preprocess::DEFAULT_THRESHOLD);
+                ui.add(egui::Slider::new(threshold, 0..=255).text("기준"));
+            } else {
+                adjust.threshold = None;
+            }
+            if ui
+                .add_enabled(!adjust.is_identity(), egui::Button::new("초기화").small())
+                .clicked()
+            {
+                *adjust = ImageAdjust::default();
+            }
+        });
+    *adjust != before
 }
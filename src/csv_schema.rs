@@ -0,0 +1,134 @@
+/*
+ * SPDX-FileCopyrightText: © 2025 Jinwoo Park (pmnxis@gmail.com)
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+//! CSV column layouts: which transaction field goes into which column,
+//! under which header and in what order.
+
+use crate::model::CardTransaction;
+
+/// A transaction value that can fill a CSV column
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsvField {
+    Filename,
+    /// `01.22 16:35` (sc-expense format)
+    ShortDateTime,
+    /// `2026-01-22`
+    Date,
+    /// `16:35`
+    Time,
+    Merchant,
+    /// Expense type when set, merchant otherwise (the sc-expense extension reads this column)
+    ExpenseOrMerchant,
+    ExpenseType,
+    Amount,
+    Status,
+    /// Tags joined with `; `
+    Tags,
+}
+
+impl CsvField {
+    /// Cell text for this field, unquoted. `Amount` is handled by the caller
+    /// because its format is a separate setting. `seconds` adds `:SS` to `Time`;
+    /// `ShortDateTime` keeps the sc-expense format regardless.
+    pub fn text(self, t: &CardTransaction, seconds: bool) -> String {
+        match self {
+            Self::Filename => t.filename.clone(),
+            Self::ShortDateTime => t.datetime.format("%m.%d %H:%M").to_string(),
+            Self::Date => t.datetime.format("%Y-%m-%d").to_string(),
+            Self::Time if seconds => t.datetime.format("%H:%M:%S").to_string(),
+            Self::Time => t.datetime.format("%H:%M").to_string(),
+            Self::Merchant => t.merchant.clone(),
+            Self::ExpenseOrMerchant => t.expense_type.clone().unwrap_or_else(|| t.merchant.clone()),
+            Self::ExpenseType => t.expense_type.clone().unwrap_or_default(),
+            Self::Amount => t.amount.to_string(),
+            Self::Status => t.status.clone().unwrap_or_default(),
+            Self::Tags => t.tags.join("; "),
+        }
+    }
+}
+
+/// Ordered (field, header) columns of a CSV export
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CsvSchema {
+    /// Name shown in the preset picker
+    pub name: &'static str,
+    pub columns: &'static [(CsvField, &'static str)],
+    /// Header of the optional memo column appended last
+    pub memo_header: &'static str,
+}
+
+impl CsvSchema {
+    /// The original layout, read by the sc-expense Chrome extension
+    pub const SC_EXPENSE: Self = Self {
+        name: "sc-expense",
+        columns: &[
+            (CsvField::Filename, "파일명"),
+            (CsvField::ShortDateTime, "날짜"),
+            (CsvField::ExpenseOrMerchant, "가맹점"),
+            (CsvField::Amount, "금액"),
+        ],
+        memo_header: "메모",
+    };
+
+    pub const KOREAN: Self = Self {
+        name: "일반 (한국어)",
+        columns: &[
+            (CsvField::Date, "날짜"),
+            (CsvField::Time, "시간"),
+            (CsvField::Merchant, "가맹점"),
+            (CsvField::ExpenseType, "비용종류"),
+            (CsvField::Amount, "금액"),
+            (CsvField::Status, "상태"),
+            (CsvField::Tags, "태그"),
+            (CsvField::Filename, "파일명"),
+        ],
+        memo_header: "메모",
+    };
+
+    pub const ENGLISH: Self = Self {
+        name: "English",
+        columns: &[
+            (CsvField::Date, "Date"),
+            (CsvField::Time, "Time"),
+            (CsvField::Merchant, "Merchant"),
+            (CsvField::ExpenseType, "Category"),
+            (CsvField::Amount, "Amount (KRW)"),
+            (CsvField::Status, "Status"),
+            (CsvField::Tags, "Tags"),
+            (CsvField::Filename, "File"),
+        ],
+        memo_header: "Memo",
+    };
+
+    pub const PRESETS: [Self; 3] = [Self::SC_EXPENSE, Self::KOREAN, Self::ENGLISH];
+
+    /// Preset that wrote this header row, and whether its memo column follows
+    pub fn from_headers(headers: &[String]) -> Option<(Self, bool)> {
+        Self::PRESETS.into_iter().find_map(|schema| {
+            let n = schema.columns.len();
+            let columns_match = headers.len() >= n
+                && schema
+                    .columns
+                    .iter()
+                    .zip(headers)
+                    .all(|((_, h), header)| header.trim() == *h);
+            match headers.get(n) {
+                _ if !columns_match => None,
+                None => Some((schema, false)),
+                Some(memo) if memo.trim() == schema.memo_header && headers.len() == n + 1 => {
+                    Some((schema, true))
+                }
+                Some(_) => None,
+            }
+        })
+    }
+}
+
+impl Default for CsvSchema {
+    fn default() -> Self {
+        Self::SC_EXPENSE
+    }
+}
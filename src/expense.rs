@@ -7,6 +7,8 @@
 //! Expense type detection based on merchant keyword matching.
 //! Rules ported from sc-expense Chrome extension (popup.js).
 
+use serde::{Deserialize, Serialize};
+
 /// Expense recommendation from keyword matching
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
@@ -98,29 +100,94 @@ const KNOWN_LABELS: &[&str] = &[
     "Gas", "Tollgate", "Highpass", "Taxi", "Express", "Telecom", "Parking",
 ];
 
-/// Detect expense type from merchant name using sc-expense keyword rules.
-/// Returns None if no rule matches.
-pub fn detect_expense(merchant: &str) -> Option<ExpenseRecommendation> {
-    let trimmed = merchant.trim();
+/// A keyword rule as used at runtime; built from `RULES`, reorderable and toggleable
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuleEntry {
+    pub label: String,
+    pub category: String,
+    pub keywords: Vec<String>,
+    pub two_line: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Active expense rules in priority order: the first enabled match wins
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExpenseRules {
+    pub rules: Vec<RuleEntry>,
+    /// Team-specific noise stripped from parsed merchant names (see `parser::clean_merchant_noise`)
+    #[serde(default)]
+    pub noise_tokens: Vec<String>,
+}
+
+impl Default for ExpenseRules {
+    fn default() -> Self {
+        Self {
+            rules: RULES
+                .iter()
+                .map(|r| RuleEntry {
+                    label: r.label.to_string(),
+                    category: r.category.to_string(),
+                    keywords: r.keywords.iter().map(|k| k.to_string()).collect(),
+                    two_line: r.two_line,
+                    enabled: true,
+                })
+                .collect(),
+            noise_tokens: Vec::new(),
+        }
+    }
+}
 
-    // If already a known label, no recommendation needed
-    if KNOWN_LABELS.contains(&trimmed) {
-        return None;
+impl ExpenseRules {
+    /// Detect expense type from merchant name using sc-expense keyword rules.
+    /// Returns None if no enabled rule matches.
+    pub fn detect(&self, merchant: &str) -> Option<ExpenseRecommendation> {
+        self.detect_all(merchant).into_iter().next()
     }
 
-    for rule in RULES {
-        for keyword in rule.keywords {
-            if trimmed.contains(keyword) {
-                return Some(ExpenseRecommendation {
-                    label: rule.label.to_string(),
-                    category: rule.category.to_string(),
+    /// Every enabled rule matching the merchant, in priority order, one per label.
+    /// The first entry is what `detect` returns.
+    pub fn detect_all(&self, merchant: &str) -> Vec<ExpenseRecommendation> {
+        let trimmed = merchant.trim();
+
+        // If already a known label, no recommendation needed
+        if KNOWN_LABELS.contains(&trimmed) {
+            return Vec::new();
+        }
+
+        let mut found: Vec<ExpenseRecommendation> = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.enabled) {
+            if rule.keywords.iter().any(|k| trimmed.contains(k.as_str()))
+                && !found.iter().any(|r| r.label == rule.label)
+            {
+                found.push(ExpenseRecommendation {
+                    label: rule.label.clone(),
+                    category: rule.category.clone(),
                     two_line: rule.two_line,
                 });
             }
         }
+        found
     }
 
-    None
+    /// Swap a rule with its neighbour; `up` raises its priority
+    pub fn move_rule(&mut self, idx: usize, up: bool) {
+        let other = if up {
+            idx.checked_sub(1)
+        } else {
+            Some(idx + 1)
+        };
+        if let Some(other) = other
+            && other < self.rules.len()
+            && idx < self.rules.len()
+        {
+            self.rules.swap(idx, other);
+        }
+    }
 }
 
 /// Generate the fee note string for CSV output.
@@ -17,33 +17,99 @@ pub fn setup_fonts(ctx: &egui::Context) {
     {
         if let Some(shsans_data) = wasm_font_cache::get("SourceHanSansVF-remapped.otf") {
             log::info!("Loaded Source Han Sans ({} bytes)", shsans_data.len());
-            fonts.font_data.insert(
-                "Source Han Sans".to_owned(),
-                Arc::new(egui::FontData::from_owned(shsans_data).weight(400)),
+            install_korean_font(
+                &mut fonts,
+                egui::FontData::from_owned(shsans_data).weight(400),
             );
-
-            // Insert at the front of Proportional (default body text)
-            fonts
-                .families
-                .entry(egui::FontFamily::Proportional)
-                .or_default()
-                .insert(0, "Source Han Sans".to_owned());
-
-            // Also add as fallback for Monospace
-            fonts
-                .families
-                .entry(egui::FontFamily::Monospace)
-                .or_default()
-                .push("Source Han Sans".to_owned());
         } else {
             log::warn!("Source Han Sans font not found in cache");
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        match desktop_font::load() {
+            Some((path, data)) => {
+                log::info!(
+                    "Loaded Korean font {} ({} bytes)",
+                    path.display(),
+                    data.font.len()
+                );
+                install_korean_font(&mut fonts, data);
+            }
+            None => log::warn!("No Korean font found; Hangul will not render"),
+        }
+    }
+
     ctx.set_fonts(fonts);
     log::info!("Fonts configured");
 }
 
+/// Register a font as the primary body font and as the Monospace fallback
+fn install_korean_font(fonts: &mut egui::FontDefinitions, data: egui::FontData) {
+    fonts
+        .font_data
+        .insert("Source Han Sans".to_owned(), Arc::new(data));
+
+    // Insert at the front of Proportional (default body text)
+    fonts
+        .families
+        .entry(egui::FontFamily::Proportional)
+        .or_default()
+        .insert(0, "Source Han Sans".to_owned());
+
+    // Also add as fallback for Monospace
+    fonts
+        .families
+        .entry(egui::FontFamily::Monospace)
+        .or_default()
+        .push("Source Han Sans".to_owned());
+}
+
+// ===== Desktop Font Loading =====
+
+/// The bundled font is ~30 MB, so the desktop build reads it from disk instead of
+/// embedding it, falling back to a system Korean font.
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop_font {
+    use std::path::PathBuf;
+
+    const BUNDLED: &str = "SourceHanSansVF-remapped.otf";
+
+    /// Common Korean system fonts (collections load their first face)
+    const SYSTEM_FONTS: &[&str] = &[
+        "C:\\Windows\\Fonts\\malgun.ttf",
+        "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+        "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+        "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+        "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+        "/usr/share/fonts/truetype/nanum/NanumGothic.ttf",
+    ];
+
+    /// First readable candidate: `Fonts/` next to the executable (same layout as the
+    /// web dist), then `web_fonts/` in the source tree (`cargo run`), then system fonts
+    pub fn load() -> Option<(PathBuf, egui::FontData)> {
+        let exe_dir = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.to_path_buf()));
+        let bundled = exe_dir
+            .map(|d| d.join("Fonts").join(BUNDLED))
+            .into_iter()
+            .chain([PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("web_fonts")
+                .join(BUNDLED)]);
+        for path in bundled {
+            if let Ok(bytes) = std::fs::read(&path) {
+                return Some((path, egui::FontData::from_owned(bytes).weight(400)));
+            }
+        }
+        SYSTEM_FONTS.iter().map(PathBuf::from).find_map(|path| {
+            let bytes = std::fs::read(&path).ok()?;
+            Some((path, egui::FontData::from_owned(bytes)))
+        })
+    }
+}
+
 // ===== WASM Font Cache =====
 
 #[cfg(target_arch = "wasm32")]
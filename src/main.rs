@@ -7,11 +7,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod csv_schema;
 mod expense;
 mod fonts;
 mod model;
 mod parser;
 mod pdf_export;
+mod preprocess;
 mod table;
 
 #[cfg(target_arch = "wasm32")]
@@ -36,7 +38,12 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "카드 영수증 OCR",
         native_options,
-        Box::new(|cc| Ok(Box::new(CardReceiptApp::new(cc)))),
+        Box::new(|cc| {
+            let app = CardReceiptApp::new(cc);
+            // Bundled Source Han Sans, or a Korean system font
+            crate::fonts::setup_fonts(&cc.egui_ctx);
+            Ok(Box::new(app))
+        }),
     )
 }
 
@@ -50,6 +57,9 @@ fn main() {
 
     let web_options = eframe::WebOptions::default();
 
+    // Warm up the OCR worker in the background; the UI waits on ocr::is_ready
+    wasm_bindgen_futures::spawn_local(crate::ocr::init());
+
     wasm_bindgen_futures::spawn_local(async {
         // Preload fonts before starting eframe
         crate::fonts::preload_fonts().await;
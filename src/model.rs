@@ -4,21 +4,171 @@
  * SPDX-License-Identifier: MIT
  */
 
-use chrono::NaiveDateTime;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 
+use crate::csv_schema::{CsvField, CsvSchema};
+use crate::expense::{self, ExpenseRules};
+use crate::parser;
+use crate::pdf_export::PdfLayout;
+use crate::preprocess::ImageAdjust;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CardTransaction {
+    /// Session-unique id that survives sorting and reordering (assigned by `AppState`)
+    #[serde(skip)]
+    pub id: u64,
+    /// Position in upload order (or the user's manual order); drives the `#` sort
+    #[serde(default)]
+    pub original_index: usize,
     pub filename: String,
     pub datetime: NaiveDateTime,
     pub merchant: String,
-    pub amount: u64,
+    /// Amount in won; negative for refunds/cancellations
+    pub amount: i64,
+    /// Amount as first read by OCR, kept to catch typos when it is edited
+    #[serde(default)]
+    pub parsed_amount: Option<i64>,
     pub raw_ocr_text: String,
     pub card_format: CardFormat,
     /// User-confirmed expense type label (e.g., "Taxi", "Gas")
     pub expense_type: Option<String>,
+    /// Set when `expense_type` was filled by auto-apply and not yet confirmed by the user
+    #[serde(default)]
+    pub expense_auto: bool,
+    /// Parsed only partially (missing date/amount/merchant); shown for manual review
+    #[serde(default)]
+    pub needs_review: bool,
+    /// Why the row needs review (e.g. "거래일시를 찾을 수 없습니다")
+    #[serde(default)]
+    pub review_notes: Vec<String>,
+    /// Free-form note from the user (e.g. "고객 접대 - 김부장")
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Free-form tags (e.g. "출장", "1월"); independent of `expense_type`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Amount in the original currency for overseas purchases (현지승인금액)
+    #[serde(default)]
+    pub foreign_amount: Option<ForeignAmount>,
+    /// Approval status from 승인상태/거래상태 (e.g. "정상", "취소", "매입")
+    #[serde(default)]
+    pub status: Option<String>,
+    /// 공급가액 (amount before VAT), when the receipt shows the breakdown
+    #[serde(default)]
+    pub supply_amount: Option<i64>,
+    /// 부가세 (VAT)
+    #[serde(default)]
+    pub vat_amount: Option<i64>,
+    /// Card number with all but the last four digits masked (e.g. "****-****-****-5678")
+    #[serde(default)]
+    pub card_number: Option<String>,
+    /// 승인번호
+    #[serde(default)]
+    pub approval_number: Option<String>,
+    /// 사업자등록번호 of the merchant as `123-45-67890` (check digit not guaranteed)
+    #[serde(default)]
+    pub business_number: Option<String>,
+    #[serde(skip)]
+    pub image_bytes: Arc<[u8]>,
+    /// Further supporting images (e.g. an itemized receipt next to the card slip)
+    #[serde(skip)]
+    pub extra_images: Vec<Arc<[u8]>>,
+    /// Word boxes from OCR, for highlighting in the preview (not persisted with the image)
     #[serde(skip)]
-    pub image_bytes: Vec<u8>,
+    pub ocr_layout: Option<OcrLayout>,
+    /// Which `ocr_layout` words each parsed field came from
+    #[serde(skip)]
+    pub field_words: FieldWords,
+}
+
+/// An amount in a currency other than KRW
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ForeignAmount {
+    /// ISO code, e.g. "USD"
+    pub currency: String,
+    pub amount: f64,
+}
+
+/// A word recognized by OCR with its bounding box `[x0, y0, x1, y1]`
+/// in original image pixels
+#[derive(Clone, Debug)]
+pub struct Word {
+    pub text: String,
+    pub bbox: [f32; 4],
+}
+
+/// A text line recognized by OCR with its bounding box `[x0, y0, x1, y1]`
+/// in original image pixels
+#[derive(Clone, Debug)]
+pub struct OcrLine {
+    pub text: String,
+    pub bbox: [f32; 4],
+}
+
+/// OCR words and lines plus the size of the image their boxes refer to
+#[derive(Clone, Debug)]
+pub struct OcrLayout {
+    pub width: f32,
+    pub height: f32,
+    pub words: Vec<Word>,
+    pub lines: Vec<OcrLine>,
+}
+
+/// Indices into `OcrLayout::words` that fed each parsed field
+#[derive(Clone, Debug, Default)]
+pub struct FieldWords {
+    pub datetime: Vec<usize>,
+    pub merchant: Vec<usize>,
+    pub amount: Vec<usize>,
+}
+
+impl CardTransaction {
+    /// The receipt says the approval was cancelled (취소, 승인취소, ...)
+    pub fn is_cancelled(&self) -> bool {
+        self.status.as_deref().is_some_and(|s| s.contains("취소"))
+    }
+
+    /// After a user edit the row counts as confirmed once the fields a report needs
+    /// are filled; otherwise it stays flagged with what is missing.
+    /// `unreadable_amount` is typed amount text that didn't parse.
+    pub fn recheck_review(&mut self, unreadable_amount: Option<&str>) {
+        let mut missing = Vec::new();
+        if self.datetime == unset_datetime() {
+            missing.push("거래일시를 입력해야 합니다".to_string());
+        }
+        if let Some(text) = unreadable_amount {
+            missing.push(format!("금액을 읽을 수 없습니다 ({})", text));
+        }
+        if self.merchant.trim().is_empty() {
+            missing.push("가맹점명이 비어 있습니다".to_string());
+        }
+        self.needs_review = !missing.is_empty();
+        self.review_notes = missing;
+    }
+}
+
+/// Sentinel datetime for rows whose date could not be parsed
+pub fn unset_datetime() -> NaiveDateTime {
+    DateTime::UNIX_EPOCH.naive_utc()
+}
+
+/// Current local wall-clock time, used to spot future-dated (misread) transactions
+#[cfg(not(target_arch = "wasm32"))]
+pub fn local_now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Current local wall-clock time, used to spot future-dated (misread) transactions
+#[cfg(target_arch = "wasm32")]
+pub fn local_now() -> NaiveDateTime {
+    let d = js_sys::Date::new_0();
+    chrono::NaiveDate::from_ymd_opt(d.get_full_year() as i32, d.get_month() + 1, d.get_date())
+        .and_then(|date| date.and_hms_opt(d.get_hours(), d.get_minutes(), d.get_seconds()))
+        .unwrap_or_else(|| chrono::Utc::now().naive_utc())
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -44,9 +194,29 @@ impl std::fmt::Display for CardFormat {
 pub struct PendingImage {
     pub filename: String,
     pub bytes: Vec<u8>,
+    /// Adjustments applied to the OCR input only; the original is kept for preview/PDF
+    pub adjust: ImageAdjust,
+}
+
+/// Totals for a tax-invoice style breakdown (see `AppState::tax_summary`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TaxSummary {
+    pub supply: i64,
+    pub vat: i64,
+    pub total: i64,
+    /// Rows whose VAT was estimated rather than read from the receipt
+    pub estimated: usize,
+}
+
+/// An image whose OCR failed; kept so it still reaches the PDF/ZIP bundle
+#[derive(Clone, Debug)]
+pub struct FailedImage {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub reason: String,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SortColumn {
     Index,
     DateTime,
@@ -54,12 +224,269 @@ pub enum SortColumn {
     Amount,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// Table columns that can be hidden; #, 날짜, 가맹점 and 금액 are always shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OptionalColumn {
+    ExpenseType,
+    Status,
+    CardFormat,
+    ApprovalNumber,
+    Tags,
+    Memo,
+}
+
+impl OptionalColumn {
+    /// In table order
+    pub const ALL: [Self; 6] = [
+        Self::ExpenseType,
+        Self::Status,
+        Self::CardFormat,
+        Self::ApprovalNumber,
+        Self::Tags,
+        Self::Memo,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ExpenseType => "비용종류",
+            Self::Status => "상태",
+            Self::CardFormat => "카드사",
+            Self::ApprovalNumber => "승인번호",
+            Self::Tags => "태그",
+            Self::Memo => "메모",
+        }
+    }
+
+    /// Columns shown until the user changes them
+    pub fn default_visible() -> BTreeSet<Self> {
+        BTreeSet::from([Self::ExpenseType, Self::Status])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SortDirection {
     Ascending,
     Descending,
 }
 
+/// Default cap on simultaneous OCR tasks; more than this starves the single Tesseract worker
+pub const DEFAULT_OCR_CONCURRENCY: usize = 4;
+
+/// Undo steps kept; older snapshots are dropped
+pub const UNDO_DEPTH: usize = 20;
+
+/// Expense label color unless the user picked another one
+pub const DEFAULT_EXPENSE_COLOR: [u8; 3] = [100, 200, 100];
+
+/// Default UTC offset of receipt times: Asia/Seoul (KST, no daylight saving)
+pub const DEFAULT_UTC_OFFSET_MINUTES: i32 = 9 * 60;
+
+/// One row of the JSON export
+#[derive(Serialize)]
+struct JsonTransaction<'a> {
+    no: usize,
+    /// ISO-8601 with offset, e.g. `2026-01-22T16:35:39+09:00`
+    datetime: String,
+    merchant: &'a str,
+    amount: i64,
+    foreign_amount: Option<&'a ForeignAmount>,
+    expense_type: Option<&'a str>,
+    status: Option<&'a str>,
+    card_number: Option<&'a str>,
+    approval_number: Option<&'a str>,
+    business_number: Option<&'a str>,
+    memo: Option<&'a str>,
+    /// Only written when `minimize_personal_info` is off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_ocr_text: Option<String>,
+    tags: &'a [String],
+    filename: &'a str,
+}
+
+/// Table columns that can be edited in place
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InlineField {
+    Merchant,
+    Amount,
+}
+
+/// Which of the two merged rows a field is taken from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeSource {
+    First,
+    Second,
+}
+
+/// Amount of a merged row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeAmount {
+    /// Both rows' amounts added (e.g. supply and VAT read as two rows)
+    Sum,
+    Keep(MergeSource),
+}
+
+/// Which fields win in `AppState::merge`; everything else follows `image`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MergeChoices {
+    pub amount: MergeAmount,
+    pub datetime: MergeSource,
+    pub image: MergeSource,
+}
+
+impl Default for MergeChoices {
+    fn default() -> Self {
+        Self {
+            amount: MergeAmount::Keep(MergeSource::First),
+            datetime: MergeSource::First,
+            image: MergeSource::First,
+        }
+    }
+}
+
+/// In-place edit of one table cell; `text` holds the uncommitted value
+#[derive(Clone, Debug)]
+pub struct InlineEdit {
+    pub row: usize,
+    pub field: InlineField,
+    pub text: String,
+    /// Focus is requested once, on the first frame of the edit
+    pub focused: bool,
+}
+
+/// Whether two amounts differ by a digit or more (a ratio of 5x either way),
+/// the usual size of a typo such as an extra or missing zero
+pub fn amounts_far_apart(a: i64, b: i64) -> bool {
+    let (a, b) = (a.unsigned_abs(), b.unsigned_abs());
+    a.max(b) >= a.min(b).saturating_mul(5).max(1) && a != b
+}
+
+/// Parse a user-typed or imported amount: any `DigitGrouping` separator is ignored
+/// and a leading minus (also the Unicode minus sign) marks a refund. A '.' must
+/// group three digits ("45.000"); "45.5" is rejected rather than read as 455.
+pub fn parse_amount_input(text: &str) -> Option<i64> {
+    if text
+        .trim()
+        .split('.')
+        .skip(1)
+        .any(|group| group.len() != 3 || !group.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    text.replace([',', '.', ' ', '\u{a0}'], "")
+        .replace('−', "-")
+        .parse::<i64>()
+        .ok()
+}
+
+/// Table/export row filters; every set condition must match (they stack)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RowFilter {
+    /// Case-insensitive substring of the merchant name, memo or a tag
+    pub search: String,
+    /// Only rows with this expense type (`UNCATEGORIZED_LABEL` = rows without one)
+    pub expense: Option<String>,
+    /// Only rows carrying this tag
+    pub tag: Option<String>,
+    /// Inclusive date range
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    /// Inclusive KRW amount range
+    pub amount_min: Option<i64>,
+    pub amount_max: Option<i64>,
+    /// Only rows dated in this (year, month); set by the month tabs
+    pub month: Option<(i32, u32)>,
+}
+
+impl RowFilter {
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    pub fn matches(&self, t: &CardTransaction) -> bool {
+        let query = self.search.trim().to_lowercase();
+        let text_ok = query.is_empty()
+            || t.merchant.to_lowercase().contains(&query)
+            || t.memo
+                .as_deref()
+                .is_some_and(|m| m.to_lowercase().contains(&query))
+            || t.tags.iter().any(|tag| tag.to_lowercase().contains(&query));
+        let expense_ok = self
+            .expense
+            .as_deref()
+            .is_none_or(|e| t.expense_type.as_deref().unwrap_or(UNCATEGORIZED_LABEL) == e);
+        let tag_ok = self.tag.as_ref().is_none_or(|tag| t.tags.contains(tag));
+        let date = t.datetime.date();
+        let date_ok =
+            self.date_from.is_none_or(|d| date >= d) && self.date_to.is_none_or(|d| date <= d);
+        let amount_ok = self.amount_min.is_none_or(|m| t.amount >= m)
+            && self.amount_max.is_none_or(|m| t.amount <= m);
+        let month_ok = self
+            .month
+            .is_none_or(|(y, m)| date.year() == y && date.month() == m);
+        text_ok && expense_ok && tag_ok && date_ok && amount_ok && month_ok
+    }
+}
+
+/// Parse a filter date typed as `2026-01-15`, `2026.01.15` or `2026/01/15`
+pub fn parse_filter_date(text: &str) -> Option<NaiveDate> {
+    let normalized = text.trim().replace(['.', '/'], "-");
+    NaiveDate::parse_from_str(normalized.trim_end_matches('-'), "%Y-%m-%d").ok()
+}
+
+/// Thousands separator for displayed and exported amounts (input fields keep commas)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DigitGrouping {
+    /// `45,000`
+    #[default]
+    Comma,
+    /// `45.000`, for European accounting imports
+    Period,
+    /// `45 000`
+    Space,
+}
+
+impl DigitGrouping {
+    pub const ALL: [Self; 3] = [Self::Comma, Self::Period, Self::Space];
+
+    pub fn separator(self) -> char {
+        match self {
+            Self::Comma => ',',
+            Self::Period => '.',
+            Self::Space => ' ',
+        }
+    }
+}
+
+/// `amount` with comma thousands separators, as typed in the amount input fields
+pub fn format_amount(amount: i64) -> String {
+    format_amount_grouped(amount, DigitGrouping::Comma)
+}
+
+/// `amount` with thousands separated by `grouping` (`45,000`, `45.000`, `45 000`)
+pub fn format_amount_grouped(amount: i64, grouping: DigitGrouping) -> String {
+    let s = amount.unsigned_abs().to_string();
+    let mut result = String::new();
+    for (i, c) in s.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.push(grouping.separator());
+        }
+        result.push(c);
+    }
+    if amount < 0 {
+        result.push('-');
+    }
+    result.chars().rev().collect()
+}
+
+/// Amount column style for CSV exports
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsvAmountFormat {
+    /// Plain integer (`45000`); Excel reads it as a number
+    Raw,
+    /// Thousands-separated with `AppState::digit_grouping`, quoted (`"45,000"`)
+    Thousands,
+}
+
 pub struct AppState {
     pub transactions: Vec<CardTransaction>,
     pub pending_images: Vec<PendingImage>,
@@ -68,7 +495,84 @@ pub struct AppState {
     pub ocr_in_progress: bool,
     pub status_message: String,
     pub error_messages: Vec<String>,
-    pub selected_index: Option<usize>,
+    /// Images OCR could not read, appended to PDF/ZIP exports for manual entry
+    pub failed_images: Vec<FailedImage>,
+    /// Append `failed_images` to full PDF/ZIP exports
+    pub include_failed_images: bool,
+    /// UTC offset the (naive) receipt times are in; only used for the JSON export
+    pub utc_offset_minutes: i32,
+    /// Row open in the side panel, by `CardTransaction::id` so sorting keeps it
+    pub selected_id: Option<u64>,
+    /// Next `CardTransaction::id` to hand out
+    next_id: u64,
+    /// Scroll the table to the selected row on the next frame (set after a re-sort)
+    pub scroll_to_selected: bool,
+    /// Show per-row checkboxes for acting on several rows at once
+    pub multi_select: bool,
+    /// Rows checked in multi-select mode (positions in `transactions`)
+    pub selected_rows: BTreeSet<usize>,
+    /// Table cell currently being edited in place
+    pub inline_edit: Option<InlineEdit>,
+    /// Automatically apply the recommended expense type to newly parsed transactions
+    pub auto_apply_expense: bool,
+    /// Start OCR as soon as images are added, without the "OCR 인식 시작" click
+    pub auto_start_ocr: bool,
+    /// Detect text orientation (Tesseract OSD) and rotate before OCR; adds latency
+    pub auto_rotate: bool,
+    /// Parsed amounts above this many won are treated as OCR misreads
+    pub amount_ceiling: i64,
+    /// Advisory per-report budget in won; the total turns red above it
+    pub budget_limit: Option<i64>,
+    /// Re-run OCR on a row's image after "이미지 교체" and refill its parsed fields
+    pub rerun_ocr_on_replace: bool,
+    /// Add one CSV per expense type under `by_category/` in the ZIP export
+    pub export_csv_by_category: bool,
+    /// Keep original image filenames in the ZIP instead of numbering them
+    pub zip_keep_filenames: bool,
+    /// Maximum number of OCR tasks running at once
+    pub ocr_concurrency: usize,
+    /// Append a 메모 column to CSV exports
+    pub csv_include_memo: bool,
+    /// How the 금액 column is written in CSV exports
+    pub csv_amount_format: CsvAmountFormat,
+    /// Thousands separator in the table, totals, HTML/PDF and formatted CSV amounts
+    pub digit_grouping: DigitGrouping,
+    /// CSV column layout preset
+    pub csv_schema: CsvSchema,
+    /// Draw boxes over the preview where the date/merchant/amount were read
+    pub show_ocr_highlights: bool,
+    /// Leave cancelled (취소) transactions out of totals and exports
+    pub exclude_cancelled: bool,
+    /// Page layout for PDF exports
+    pub pdf_layout: PdfLayout,
+    /// Name the source image file on each PDF page
+    pub pdf_include_filename: bool,
+    /// Group table rows by expense type with a subtotal row after each group
+    pub group_by_expense: bool,
+    /// Hide 0원 rows (often a phantom 봉사료 line) from the table without deleting them
+    pub hide_zero_amount: bool,
+    /// Also leave hidden 0원 rows out of CSV/ZIP/PDF exports
+    pub exclude_zero_from_export: bool,
+    /// Leave the raw OCR text and card number out of exports (CSV never has them)
+    pub minimize_personal_info: bool,
+    /// Embed receipt thumbnails in the HTML export (makes the file much larger)
+    pub html_include_images: bool,
+    /// Merchant names seen so far, offered as autocomplete suggestions
+    pub known_merchants: BTreeSet<String>,
+    /// Expense keyword rules in priority order (user-reorderable)
+    pub expense_rules: ExpenseRules,
+    /// Show seconds in the table's time and the CSV 시간 column
+    pub show_seconds: bool,
+    /// Optional table columns currently shown
+    pub visible_columns: BTreeSet<OptionalColumn>,
+    /// Table color per expense label, as sRGB; labels not listed use `DEFAULT_EXPENSE_COLOR`
+    pub expense_colors: BTreeMap<String, [u8; 3]>,
+    /// Search / expense / date / amount filters applied to the table, totals and exports
+    pub filter: RowFilter,
+    /// Snapshots of `transactions` taken before each edit (images are shared, not copied)
+    pub undo_stack: Vec<Vec<CardTransaction>>,
+    /// (title, text) of the raw OCR text window opened from the row menu
+    pub ocr_text_view: Option<(String, String)>,
 }
 
 impl AppState {
@@ -81,61 +585,1328 @@ impl AppState {
             ocr_in_progress: false,
             status_message: "이미지를 업로드하세요".into(),
             error_messages: Vec::new(),
-            selected_index: None,
+            failed_images: Vec::new(),
+            include_failed_images: true,
+            utc_offset_minutes: DEFAULT_UTC_OFFSET_MINUTES,
+            selected_id: None,
+            next_id: 0,
+            scroll_to_selected: false,
+            multi_select: false,
+            selected_rows: BTreeSet::new(),
+            inline_edit: None,
+            auto_apply_expense: false,
+            auto_start_ocr: false,
+            auto_rotate: false,
+            rerun_ocr_on_replace: true,
+            amount_ceiling: parser::DEFAULT_AMOUNT_CEILING,
+            budget_limit: None,
+            export_csv_by_category: false,
+            zip_keep_filenames: false,
+            ocr_concurrency: DEFAULT_OCR_CONCURRENCY,
+            csv_include_memo: false,
+            csv_amount_format: CsvAmountFormat::Raw,
+            digit_grouping: DigitGrouping::default(),
+            csv_schema: CsvSchema::default(),
+            show_ocr_highlights: true,
+            exclude_cancelled: false,
+            pdf_layout: PdfLayout::OnePerPage,
+            pdf_include_filename: false,
+            group_by_expense: false,
+            hide_zero_amount: false,
+            exclude_zero_from_export: false,
+            minimize_personal_info: true,
+            html_include_images: false,
+            known_merchants: BTreeSet::new(),
+            expense_rules: ExpenseRules::default(),
+            expense_colors: BTreeMap::new(),
+            visible_columns: OptionalColumn::default_visible(),
+            show_seconds: false,
+            filter: RowFilter::default(),
+            undo_stack: Vec::new(),
+            ocr_text_view: None,
         }
     }
 
-    pub fn sort_transactions(&mut self) {
-        let dir = &self.sort_direction;
-        match self.sort_column {
-            SortColumn::Index => {} // natural order
-            SortColumn::DateTime => self.transactions.sort_by(|a, b| {
-                let cmp = a.datetime.cmp(&b.datetime);
-                if *dir == SortDirection::Descending {
-                    cmp.reverse()
-                } else {
-                    cmp
+    /// Clear the session (transactions, queue, messages) but keep user preferences
+    pub fn reset(&mut self) {
+        *self = Self {
+            multi_select: self.multi_select,
+            auto_apply_expense: self.auto_apply_expense,
+            auto_start_ocr: self.auto_start_ocr,
+            auto_rotate: self.auto_rotate,
+            rerun_ocr_on_replace: self.rerun_ocr_on_replace,
+            amount_ceiling: self.amount_ceiling,
+            budget_limit: self.budget_limit,
+            include_failed_images: self.include_failed_images,
+            utc_offset_minutes: self.utc_offset_minutes,
+            export_csv_by_category: self.export_csv_by_category,
+            zip_keep_filenames: self.zip_keep_filenames,
+            ocr_concurrency: self.ocr_concurrency,
+            csv_include_memo: self.csv_include_memo,
+            csv_amount_format: self.csv_amount_format,
+            digit_grouping: self.digit_grouping,
+            csv_schema: self.csv_schema,
+            show_ocr_highlights: self.show_ocr_highlights,
+            exclude_cancelled: self.exclude_cancelled,
+            pdf_layout: self.pdf_layout,
+            pdf_include_filename: self.pdf_include_filename,
+            group_by_expense: self.group_by_expense,
+            hide_zero_amount: self.hide_zero_amount,
+            exclude_zero_from_export: self.exclude_zero_from_export,
+            minimize_personal_info: self.minimize_personal_info,
+            html_include_images: self.html_include_images,
+            known_merchants: std::mem::take(&mut self.known_merchants),
+            expense_rules: std::mem::take(&mut self.expense_rules),
+            expense_colors: std::mem::take(&mut self.expense_colors),
+            visible_columns: std::mem::take(&mut self.visible_columns),
+            show_seconds: self.show_seconds,
+            ..Self::new()
+        };
+    }
+
+    /// Snapshot the transactions before a mutating action so `undo` can restore them.
+    /// Image bytes are shared (`Arc`), so a snapshot doesn't copy them.
+    pub fn push_undo(&mut self) {
+        self.undo_stack.push(self.transactions.clone());
+        if self.undo_stack.len() > UNDO_DEPTH {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Restore the last snapshot. Returns false when there is nothing to undo.
+    /// Rows recognized since the snapshot are kept (`push_transaction` adds them to it).
+    /// Checked rows stay checked by id, wherever the snapshot puts them.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            return false;
+        };
+        let checked: Vec<u64> = self
+            .selected_rows
+            .iter()
+            .filter_map(|&i| self.transactions.get(i).map(|t| t.id))
+            .collect();
+        self.transactions = snapshot;
+
+        if self.selected_index().is_none() {
+            self.selected_id = None;
+        }
+        self.selected_rows = checked
+            .into_iter()
+            .filter_map(|id| self.position_of(id))
+            .collect();
+        self.inline_edit = None;
+        true
+    }
+
+    /// Write an inline cell edit back to its transaction. Returns false when the
+    /// value was rejected (unparsable amount). An unchanged value is accepted without
+    /// an undo step. Like saving from the edit panel, a change confirms the row
+    /// (`CardTransaction::recheck_review`), and a merchant change drops an
+    /// auto-applied expense type that no longer matches. Re-sorts when the edited
+    /// column is the sort key.
+    pub fn commit_inline_edit(&mut self, edit: InlineEdit) -> bool {
+        let Some(t) = self.transactions.get(edit.row) else {
+            return false;
+        };
+        let merchant = edit.text.trim();
+        let amount = parse_amount_input(&edit.text);
+        let unchanged = match edit.field {
+            InlineField::Merchant => t.merchant == merchant,
+            InlineField::Amount => match amount {
+                Some(amount) => t.amount == amount,
+                None => return false,
+            },
+        };
+        if unchanged {
+            return true;
+        }
+        self.push_undo();
+        match edit.field {
+            InlineField::Merchant => {
+                let recommended = self.expense_rules.detect(merchant).map(|r| r.label);
+                let t = &mut self.transactions[edit.row];
+                t.merchant = merchant.to_string();
+                if t.expense_auto && t.expense_type.is_some() && t.expense_type != recommended {
+                    t.expense_type = None;
+                    t.expense_auto = false;
                 }
-            }),
-            SortColumn::Merchant => self.transactions.sort_by(|a, b| {
-                let cmp = a.merchant.cmp(&b.merchant);
-                if *dir == SortDirection::Descending {
-                    cmp.reverse()
-                } else {
-                    cmp
+                t.recheck_review(None);
+                self.remember_merchant(merchant);
+                if self.sort_column == SortColumn::Merchant {
+                    self.sort_transactions();
                 }
-            }),
-            SortColumn::Amount => self.transactions.sort_by(|a, b| {
-                let cmp = a.amount.cmp(&b.amount);
-                if *dir == SortDirection::Descending {
-                    cmp.reverse()
-                } else {
-                    cmp
+            }
+            InlineField::Amount => {
+                let t = &mut self.transactions[edit.row];
+                t.amount = amount.unwrap_or_default();
+                t.recheck_review(None);
+                if self.sort_column == SortColumn::Amount {
+                    self.sort_transactions();
                 }
-            }),
+            }
         }
+        true
     }
 
-    pub fn total_amount(&self) -> u64 {
-        self.transactions.iter().map(|t| t.amount).sum()
+    /// Rows dated after `now` — usually a misread year or month
+    pub fn future_dated(&self, now: NaiveDateTime) -> Vec<usize> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.datetime > now)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Move a transaction back one year, the usual fix for a misread 2-digit year.
+    /// Feb 29 falls back to Feb 28.
+    pub fn subtract_year(&mut self, idx: usize) {
+        if idx < self.transactions.len() {
+            self.push_undo();
+        }
+        if let Some(t) = self.transactions.get_mut(idx) {
+            let year = t.datetime.year() - 1;
+            t.datetime = t
+                .datetime
+                .with_year(year)
+                .or_else(|| t.datetime.with_day(28).and_then(|d| d.with_year(year)))
+                .unwrap_or(t.datetime);
+        }
+    }
+
+    /// Transactions to export: the checked rows in multi-select mode, otherwise all
+    pub fn export_selection(&self) -> Vec<&CardTransaction> {
+        if self.multi_select {
+            self.selected_rows
+                .iter()
+                .filter_map(|&i| self.transactions.get(i))
+                .filter(|t| self.is_exported(t))
+                .collect()
+        } else {
+            self.export_rows()
+        }
+    }
+
+    /// All transactions that go into CSV/ZIP exports
+    pub fn export_rows(&self) -> Vec<&CardTransaction> {
+        self.transactions
+            .iter()
+            .filter(|t| self.is_exported(t))
+            .collect()
+    }
+
+    /// Failed images to append to a full (not checked-rows) export
+    pub fn failed_for_export(&self) -> Vec<&FailedImage> {
+        if self.include_failed_images {
+            self.failed_images.iter().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Whether a row survives the export filters (row filter, hidden 0원 rows, cancellations)
+    pub fn is_exported(&self, t: &CardTransaction) -> bool {
+        let hidden_zero = self.hide_zero_amount && self.exclude_zero_from_export && t.amount == 0;
+        let cancelled = self.exclude_cancelled && t.is_cancelled();
+        self.filter.matches(t) && !hidden_zero && !cancelled
+    }
+
+    /// Positions in `transactions` shown in the table: the row filter plus 0원 hiding
+    pub fn filtered_indices(&self) -> Vec<usize> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.filter.matches(t) && !(self.hide_zero_amount && t.amount == 0))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Append a newly recognized transaction at the end of the upload order
+    pub fn push_transaction(&mut self, mut txn: CardTransaction) {
+        txn.id = self.next_id;
+        self.next_id += 1;
+        txn.original_index = self
+            .transactions
+            .iter()
+            .map(|t| t.original_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.remember_merchant(&txn.merchant);
+        // A new OCR result is not an undoable edit, so every history state gets it too
+        for snapshot in &mut self.undo_stack {
+            snapshot.push(txn.clone());
+        }
+        self.transactions.push(txn);
+    }
+
+    /// Delete a row (undoable). Selections on later rows move up with them.
+    pub fn remove_transaction(&mut self, idx: usize) {
+        if idx >= self.transactions.len() {
+            return;
+        }
+        self.push_undo();
+        let removed = self.transactions.remove(idx);
+        if self.selected_id == Some(removed.id) {
+            self.selected_id = None;
+        }
+        self.selected_rows = self
+            .selected_rows
+            .iter()
+            .filter(|&&i| i != idx)
+            .map(|&i| if i > idx { i - 1 } else { i })
+            .collect();
+        self.inline_edit = None;
+    }
+
+    /// Insert a copy of a row right below it (undoable) and select the copy for
+    /// editing, e.g. for a recurring expense. The image is shared, not copied, and
+    /// the copy gets a fresh `original_index`, so it sorts last under `#`.
+    pub fn duplicate(&mut self, idx: usize) {
+        let Some(t) = self.transactions.get(idx) else {
+            return;
+        };
+        let mut copy = t.clone();
+        copy.id = self.next_id;
+        self.next_id += 1;
+        copy.original_index = self
+            .transactions
+            .iter()
+            .map(|t| t.original_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.push_undo();
+        self.selected_id = Some(copy.id);
+        self.transactions.insert(idx + 1, copy);
+        self.selected_rows = self
+            .selected_rows
+            .iter()
+            .map(|&i| if i > idx { i + 1 } else { i })
+            .collect();
+        self.inline_edit = None;
+    }
+
+    /// Combine two rows of one purchase into one (undoable), placed where the
+    /// earlier row was. Merchants and memos are joined when they differ, tags are
+    /// unioned; other fields come from the row whose image is kept. The other
+    /// row's image and attachments become attachments of the merged row.
+    pub fn merge(&mut self, a: usize, b: usize, choices: MergeChoices) {
+        if a == b || a.max(b) >= self.transactions.len() {
+            return;
+        }
+        let (first, second) = (&self.transactions[a], &self.transactions[b]);
+        let pick = |source: MergeSource| match source {
+            MergeSource::First => first,
+            MergeSource::Second => second,
+        };
+
+        let mut merged = pick(choices.image).clone();
+        merged.original_index = first.original_index.min(second.original_index);
+        merged.datetime = pick(choices.datetime).datetime;
+        (merged.amount, merged.parsed_amount) = match choices.amount {
+            MergeAmount::Sum => (first.amount + second.amount, None),
+            MergeAmount::Keep(source) => (pick(source).amount, pick(source).parsed_amount),
+        };
+        merged.merchant = join_distinct(&first.merchant, &second.merchant, " / ");
+        merged.memo = match (first.memo.as_deref(), second.memo.as_deref()) {
+            (Some(x), Some(y)) => Some(join_distinct(x, y, "\n")),
+            (x, y) => x.or(y).map(String::from),
+        };
+        for tag in first.tags.iter().chain(&second.tags) {
+            if !merged.tags.contains(tag) {
+                merged.tags.push(tag.clone());
+            }
+        }
+        let other = match choices.image {
+            MergeSource::First => second,
+            MergeSource::Second => first,
+        };
+        if !other.image_bytes.is_empty() {
+            merged.extra_images.push(Arc::clone(&other.image_bytes));
+        }
+        merged
+            .extra_images
+            .extend(other.extra_images.iter().map(Arc::clone));
+
+        self.push_undo();
+        let (keep, drop) = (a.min(b), a.max(b));
+        self.selected_id = Some(merged.id);
+        self.transactions[keep] = merged;
+        self.transactions.remove(drop);
+        self.selected_rows.clear();
+        self.inline_edit = None;
+    }
+
+    /// Swap a row's image for another upload (undoable). The parsed fields stay
+    /// as they are; OCR boxes from the old image are dropped.
+    pub fn replace_image(&mut self, idx: usize, filename: String, bytes: Vec<u8>) {
+        if idx >= self.transactions.len() {
+            return;
+        }
+        self.push_undo();
+        let t = &mut self.transactions[idx];
+        t.filename = filename;
+        t.image_bytes = bytes.into();
+        t.ocr_layout = None;
+        t.field_words = FieldWords::default();
+    }
+
+    /// Attach a supporting image to a row (undoable)
+    pub fn add_extra_image(&mut self, idx: usize, bytes: Vec<u8>) {
+        if idx >= self.transactions.len() {
+            return;
+        }
+        self.push_undo();
+        self.transactions[idx].extra_images.push(bytes.into());
+    }
+
+    /// Detach the `k`-th supporting image of a row (undoable)
+    pub fn remove_extra_image(&mut self, idx: usize, k: usize) {
+        if self
+            .transactions
+            .get(idx)
+            .is_none_or(|t| k >= t.extra_images.len())
+        {
+            return;
+        }
+        self.push_undo();
+        self.transactions[idx].extra_images.remove(k);
+    }
+
+    /// Refill a row's parsed fields from a fresh OCR result of its image.
+    /// The user's memo, tags, expense type and extra images are kept.
+    pub fn apply_recognized(&mut self, idx: usize, parsed: CardTransaction) {
+        let Some(t) = self.transactions.get_mut(idx) else {
+            return;
+        };
+        let keep = (
+            t.id,
+            t.original_index,
+            std::mem::take(&mut t.extra_images),
+            t.memo.take(),
+            std::mem::take(&mut t.tags),
+            t.expense_type.take(),
+            t.expense_auto,
+        );
+        *t = parsed;
+        (
+            t.id,
+            t.original_index,
+            t.extra_images,
+            t.memo,
+            t.tags,
+            t.expense_type,
+            t.expense_auto,
+        ) = keep;
+        let merchant = t.merchant.clone();
+        self.remember_merchant(&merchant);
+        self.inline_edit = None;
+    }
+
+    /// Set a row's expense type as a user choice (undoable); None clears it
+    pub fn set_expense_type(&mut self, idx: usize, label: Option<String>) {
+        if idx >= self.transactions.len() {
+            return;
+        }
+        self.push_undo();
+        let t = &mut self.transactions[idx];
+        t.expense_type = label;
+        t.expense_auto = false;
+    }
+
+    /// Add a tag to a row (undoable); blank or already present tags are ignored
+    pub fn add_tag(&mut self, idx: usize, tag: &str) {
+        let tag = tag.trim();
+        if tag.is_empty()
+            || self
+                .transactions
+                .get(idx)
+                .is_none_or(|t| t.tags.iter().any(|x| x == tag))
+        {
+            return;
+        }
+        self.push_undo();
+        self.transactions[idx].tags.push(tag.to_string());
+    }
+
+    /// Remove a tag from a row (undoable)
+    pub fn remove_tag(&mut self, idx: usize, tag: &str) {
+        if self
+            .transactions
+            .get(idx)
+            .is_none_or(|t| !t.tags.iter().any(|x| x == tag))
+        {
+            return;
+        }
+        self.push_undo();
+        self.transactions[idx].tags.retain(|x| x != tag);
+    }
+
+    /// Bytes of every image kept in memory (queued, processed, failed). Rows
+    /// sharing one image (duplicated rows) count it once.
+    pub fn loaded_image_bytes(&self) -> usize {
+        let mut seen = BTreeSet::new();
+        let rows: usize = self
+            .transactions
+            .iter()
+            .flat_map(|t| std::iter::once(&t.image_bytes).chain(&t.extra_images))
+            .filter(|image| seen.insert(image.as_ptr()))
+            .map(|image| image.len())
+            .sum();
+        rows + self
+            .pending_images
+            .iter()
+            .map(|p| p.bytes.len())
+            .chain(self.failed_images.iter().map(|f| f.bytes.len()))
+            .sum::<usize>()
+    }
+
+    /// Set an expense label's color; the default color is not stored
+    pub fn set_expense_color(&mut self, label: &str, color: [u8; 3]) {
+        if color == DEFAULT_EXPENSE_COLOR {
+            self.expense_colors.remove(label);
+        } else {
+            self.expense_colors.insert(label.to_string(), color);
+        }
+    }
+
+    /// Every tag used by any row, sorted
+    pub fn all_tags(&self) -> BTreeSet<&str> {
+        self.transactions
+            .iter()
+            .flat_map(|t| t.tags.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Distinct (year, month) of the rows with a parsed date, oldest first
+    pub fn months(&self) -> BTreeSet<(i32, u32)> {
+        self.transactions
+            .iter()
+            .filter(|t| t.datetime != unset_datetime())
+            .map(|t| (t.datetime.year(), t.datetime.month()))
+            .collect()
+    }
+
+    /// Position of the selected row in `transactions`
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected_id.and_then(|id| self.position_of(id))
+    }
+
+    /// Select the row at `idx` (None clears the selection)
+    pub fn select(&mut self, idx: Option<usize>) {
+        self.selected_id = idx.and_then(|i| self.transactions.get(i)).map(|t| t.id);
+    }
+
+    /// Position of the row with this `id`
+    pub fn position_of(&self, id: u64) -> Option<usize> {
+        self.transactions.iter().position(|t| t.id == id)
+    }
+
+    /// Give every row a fresh id, e.g. after restoring a session (ids aren't persisted)
+    pub fn assign_ids(&mut self) {
+        for t in &mut self.transactions {
+            t.id = self.next_id;
+            self.next_id += 1;
+        }
+    }
+
+    /// Renumber `original_index` from the current vector order when it is
+    /// missing or inconsistent (e.g. a session saved before the field existed)
+    pub fn normalize_original_index(&mut self) {
+        let mut seen = BTreeSet::new();
+        if self
+            .transactions
+            .iter()
+            .all(|t| seen.insert(t.original_index))
+        {
+            return;
+        }
+        for (i, t) in self.transactions.iter_mut().enumerate() {
+            t.original_index = i;
+        }
+    }
+
+    /// Move a row (manual ordering, only meaningful under the `#` sort) and
+    /// renumber `original_index` so the new order sticks. Selections stay
+    /// on the same transactions.
+    pub fn move_transaction(&mut self, from: usize, to: usize) {
+        let n = self.transactions.len();
+        if from >= n || to >= n || from == to {
+            return;
+        }
+        self.push_undo();
+        let mut order: Vec<usize> = (0..n).collect();
+        let moved = order.remove(from);
+        order.insert(to, moved);
+        self.apply_order(&order);
+
+        let descending = self.sort_direction == SortDirection::Descending;
+        for (i, t) in self.transactions.iter_mut().enumerate() {
+            t.original_index = if descending { n - 1 - i } else { i };
+        }
+    }
+
+    /// Whether the same file (name and bytes) is already queued or processed.
+    /// Byte comparison only runs for entries whose name matches, so this stays cheap.
+    pub fn is_duplicate_image(&self, filename: &str, bytes: &[u8]) -> bool {
+        self.pending_images
+            .iter()
+            .any(|p| p.filename == filename && p.bytes == bytes)
+            || self
+                .transactions
+                .iter()
+                .any(|t| t.filename == filename && *t.image_bytes == *bytes)
+    }
+
+    pub fn remember_merchant(&mut self, merchant: &str) {
+        let trimmed = merchant.trim();
+        if !trimmed.is_empty() {
+            self.known_merchants.insert(trimmed.to_string());
+        }
+    }
+
+    /// Known merchants matching `query`: prefix matches first, then substring matches.
+    /// Case-insensitive; an exact match of the query itself is excluded.
+    pub fn merchant_suggestions(&self, query: &str, limit: usize) -> Vec<String> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut prefix = Vec::new();
+        let mut substring = Vec::new();
+        for name in &self.known_merchants {
+            let lower = name.to_lowercase();
+            if lower == query {
+                continue;
+            }
+            if lower.starts_with(&query) {
+                prefix.push(name.clone());
+            } else if lower.contains(&query) {
+                substring.push(name.clone());
+            }
+        }
+        prefix.extend(substring);
+        prefix.truncate(limit);
+        prefix
+    }
+
+    /// Whether rows are in upload (or manual) order
+    pub fn is_default_sort(&self) -> bool {
+        self.sort_column == SortColumn::Index && self.sort_direction == SortDirection::Ascending
+    }
+
+    /// Back to upload (or manual) order
+    pub fn reset_sort(&mut self) {
+        self.sort_column = SortColumn::Index;
+        self.sort_direction = SortDirection::Ascending;
+        self.sort_transactions();
+    }
+
+    /// Sort by the current column/direction. Row selections follow their
+    /// transactions to the new positions.
+    pub fn sort_transactions(&mut self) {
+        let txns = &self.transactions;
+        let descending = self.sort_direction == SortDirection::Descending;
+        let mut order: Vec<usize> = (0..txns.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&txns[a], &txns[b]);
+            let cmp = match self.sort_column {
+                SortColumn::Index => a.original_index.cmp(&b.original_index),
+                SortColumn::DateTime => a.datetime.cmp(&b.datetime),
+                SortColumn::Merchant => a.merchant.cmp(&b.merchant),
+                SortColumn::Amount => a.amount.cmp(&b.amount),
+            };
+            if descending { cmp.reverse() } else { cmp }
+        });
+        self.apply_order(&order);
+        // The selected row moved; bring it back into view
+        self.scroll_to_selected = self.selected_id.is_some();
+    }
+
+    /// Reorder transactions so that new position `i` holds old row `order[i]`,
+    /// remapping `selected_rows` accordingly (the selected row is tracked by id).
+    fn apply_order(&mut self, order: &[usize]) {
+        let mut new_pos = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_pos[old] = new;
+        }
+        let mut old: Vec<Option<CardTransaction>> = std::mem::take(&mut self.transactions)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.transactions = order.iter().filter_map(|&i| old[i].take()).collect();
+        self.selected_rows = self.selected_rows.iter().map(|&i| new_pos[i]).collect();
+        if let Some(edit) = &mut self.inline_edit {
+            edit.row = new_pos[edit.row];
+        }
+    }
+
+    /// Sum of domestic (KRW) amounts; refunds (negative amounts) reduce the total.
+    /// Foreign-currency rows are summed separately by `totals_by_currency`.
+    pub fn total_amount(&self) -> i64 {
+        self.transactions
+            .iter()
+            .filter(|t| self.counts_in_total(t) && t.foreign_amount.is_none())
+            .map(|t| t.amount)
+            .sum()
+    }
+
+    /// How far the (filtered) KRW total is over `budget_limit`, if it is
+    pub fn budget_excess(&self) -> Option<i64> {
+        let limit = self.budget_limit?;
+        let excess = self.total_amount() - limit;
+        (excess > 0).then_some(excess)
+    }
+
+    /// KRW total of every row regardless of the row filter (cancellations still
+    /// follow `exclude_cancelled`), to show next to the filtered `total_amount`
+    pub fn total_amount_unfiltered(&self) -> i64 {
+        self.transactions
+            .iter()
+            .filter(|t| !(self.exclude_cancelled && t.is_cancelled()) && t.foreign_amount.is_none())
+            .map(|t| t.amount)
+            .sum()
+    }
+
+    /// Totals per currency, KRW first, so won and foreign amounts are never added together
+    pub fn totals_by_currency(&self) -> Vec<(String, f64)> {
+        let mut foreign: BTreeMap<&str, f64> = BTreeMap::new();
+        for t in &self.transactions {
+            if let Some(fa) = &t.foreign_amount
+                && self.counts_in_total(t)
+            {
+                *foreign.entry(fa.currency.as_str()).or_default() += fa.amount;
+            }
+        }
+        let mut totals = vec![("KRW".to_string(), self.total_amount() as f64)];
+        totals.extend(foreign.into_iter().map(|(c, sum)| (c.to_string(), sum)));
+        totals
+    }
+
+    /// 공급가액/부가세/합계 over the KRW rows counted in totals, as on a tax invoice.
+    /// A row with only one of the two lines gets the other from its amount; rows with
+    /// neither are assumed to be 10% VAT-inclusive (VAT = amount/11).
+    pub fn tax_summary(&self) -> TaxSummary {
+        let mut summary = TaxSummary::default();
+        for t in &self.transactions {
+            if !self.counts_in_total(t) || t.foreign_amount.is_some() {
+                continue;
+            }
+            let (supply, vat) = match (t.supply_amount, t.vat_amount) {
+                (Some(supply), Some(vat)) => (supply, vat),
+                (None, Some(vat)) => (t.amount - vat, vat),
+                (Some(supply), None) => (supply, t.amount - supply),
+                _ => {
+                    summary.estimated += 1;
+                    let vat = (t.amount as f64 / 11.0).round() as i64;
+                    (t.amount - vat, vat)
+                }
+            };
+            summary.supply += supply;
+            summary.vat += vat;
+        }
+        summary.total = summary.supply + summary.vat;
+        summary
+    }
+
+    /// Whether a row's amount contributes to totals and subtotals
+    /// (filtered-out rows don't, so the footer matches what the table shows)
+    pub fn counts_in_total(&self, t: &CardTransaction) -> bool {
+        self.filter.matches(t) && !(self.exclude_cancelled && t.is_cancelled())
+    }
+
+    /// Check every row for fields that would break an expense report.
+    /// Returns (row index, problem description) pairs; empty when all rows look fine.
+    pub fn validate(&self) -> Vec<(usize, String)> {
+        let epoch = unset_datetime();
+        let now = local_now();
+        let mut issues = Vec::new();
+        for (i, t) in self.transactions.iter().enumerate() {
+            if t.amount == 0 {
+                issues.push((i, "금액이 0원입니다".to_string()));
+            }
+            if t.merchant.trim().is_empty() {
+                issues.push((i, "가맹점명이 비어 있습니다".to_string()));
+            }
+            if t.datetime == epoch {
+                issues.push((i, "날짜가 설정되지 않았습니다".to_string()));
+            } else if t.datetime > now {
+                issues.push((i, "미래 날짜입니다".to_string()));
+            }
+            if t.card_format == CardFormat::Unknown {
+                issues.push((i, "카드 형식을 알 수 없습니다".to_string()));
+            }
+        }
+        issues
+    }
+
+    /// Exported rows as a JSON array. Times carry the `utc_offset_minutes` offset
+    /// so receipts from abroad stay unambiguous; the table and CSV keep naive local times.
+    pub fn to_json(&self) -> Result<String, String> {
+        let offset = FixedOffset::east_opt(self.utc_offset_minutes * 60)
+            .ok_or_else(|| format!("잘못된 시간대: {}분", self.utc_offset_minutes))?;
+        let rows: Vec<JsonTransaction> = self
+            .export_rows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| JsonTransaction {
+                no: i + 1,
+                datetime: t
+                    .datetime
+                    .and_local_timezone(offset)
+                    .single()
+                    .map(|d| d.to_rfc3339())
+                    .unwrap_or_default(),
+                merchant: &t.merchant,
+                amount: t.amount,
+                foreign_amount: t.foreign_amount.as_ref(),
+                expense_type: t.expense_type.as_deref(),
+                status: t.status.as_deref(),
+                card_number: t
+                    .card_number
+                    .as_deref()
+                    .filter(|_| !self.minimize_personal_info),
+                approval_number: t.approval_number.as_deref(),
+                business_number: t.business_number.as_deref(),
+                // Sessions saved before masking existed may still hold full card numbers
+                raw_ocr_text: (!self.minimize_personal_info)
+                    .then(|| parser::mask_card_numbers_in_text(&t.raw_ocr_text)),
+                memo: t.memo.as_deref(),
+                tags: &t.tags,
+                filename: &t.filename,
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).map_err(|e| format!("JSON 변환 실패: {}", e))
+    }
+
+    /// Exported rows as a standalone, print-ready HTML page (embedded CSS).
+    /// With `html_include_images` each row gets an inline JPEG thumbnail.
+    pub fn to_html(&self) -> String {
+        let rows = self.export_rows();
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html lang=\"ko\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>카드 사용 내역</title>\n<style>\n",
+        );
+        html.push_str(HTML_STYLE);
+        html.push_str("</style>\n</head>\n<body>\n<h1>카드 사용 내역</h1>\n");
+        html.push_str(&format!(
+            "<p class=\"meta\">{}건 · {} 작성</p>\n",
+            rows.len(),
+            local_now().format("%Y-%m-%d %H:%M")
+        ));
+
+        html.push_str("<table>\n<thead><tr><th>#</th><th>날짜</th><th>가맹점</th><th>비용종류</th><th class=\"num\">금액 (원)</th><th>메모</th>");
+        if self.html_include_images {
+            html.push_str("<th>영수증</th>");
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+        for (i, t) in rows.iter().enumerate() {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td>{}</td>",
+                i + 1,
+                t.datetime.format("%Y-%m-%d %H:%M"),
+                html_escape(&t.merchant),
+                html_escape(t.expense_type.as_deref().unwrap_or("")),
+                format_amount_grouped(t.amount, self.digit_grouping),
+                html_escape(t.memo.as_deref().unwrap_or("")),
+            ));
+            if self.html_include_images {
+                let img = thumbnail_data_uri(&t.image_bytes)
+                    .map(|uri| format!("<img src=\"{}\" alt=\"\">", uri))
+                    .unwrap_or_default();
+                html.push_str(&format!("<td class=\"img\">{}</td>", img));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n<tfoot>\n");
+        let span = if self.html_include_images { 7 } else { 6 };
+        for (currency, total) in self.totals_by_currency() {
+            let text = if currency == "KRW" {
+                format!(
+                    "합계: {}원",
+                    format_amount_grouped(total as i64, self.digit_grouping)
+                )
+            } else {
+                format!("{} {:.2}", currency, total)
+            };
+            html.push_str(&format!(
+                "<tr><td colspan=\"{}\" class=\"num\">{}</td></tr>\n",
+                span, text
+            ));
+        }
+        html.push_str("</tfoot>\n</table>\n</body>\n</html>\n");
+        html
     }
 
     pub fn to_csv(&self) -> String {
+        self.transactions_to_csv(self.export_rows().into_iter())
+    }
+
+    /// CSV of the checked rows (all exported rows when multi-select is off) for
+    /// pasting into a spreadsheet; without the BOM, which would paste as a stray character
+    pub fn selection_to_csv(&self) -> String {
+        self.transactions_to_csv(self.export_selection().into_iter())
+            .trim_start_matches('\u{FEFF}')
+            .to_string()
+    }
+
+    /// One CSV per expense type as (label, csv) pairs, sorted by label.
+    /// Rows without an expense type go into "미분류".
+    pub fn to_csv_by_category(&self) -> Vec<(String, String)> {
+        let mut groups: BTreeMap<&str, Vec<&CardTransaction>> = BTreeMap::new();
+        for t in self.export_rows() {
+            groups
+                .entry(t.expense_type.as_deref().unwrap_or(UNCATEGORIZED_LABEL))
+                .or_default()
+                .push(t);
+        }
+        groups
+            .into_iter()
+            .map(|(label, rows)| {
+                (
+                    label.to_string(),
+                    self.transactions_to_csv(rows.into_iter()),
+                )
+            })
+            .collect()
+    }
+
+    /// Build CSV text (BOM + header + rows) for the given transactions, with the
+    /// columns of `csv_schema`. The optional memo column goes last so the schema's
+    /// columns stay fixed.
+    fn transactions_to_csv<'a>(
+        &self,
+        transactions: impl Iterator<Item = &'a CardTransaction>,
+    ) -> String {
+        let schema = &self.csv_schema;
+        let mut headers: Vec<&str> = schema.columns.iter().map(|(_, h)| *h).collect();
+        if self.csv_include_memo {
+            headers.push(schema.memo_header);
+        }
+
         // UTF-8 BOM for Excel compatibility
         let mut csv = String::from("\u{FEFF}");
-        csv.push_str("파일명,날짜,가맹점,금액\n");
-        for t in &self.transactions {
-            // Use expense_type instead of merchant when set
-            // (sc-expense Chrome extension reads this column)
-            let merchant_col = t.expense_type.as_deref().unwrap_or(&t.merchant);
-            csv.push_str(&format!(
-                "{},{},{},{}\n",
-                t.filename,
-                t.datetime.format("%m.%d %H:%M"),
-                merchant_col,
-                t.amount,
-            ));
+        csv.push_str(
+            &headers
+                .iter()
+                .map(|h| csv_field(h))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+        for t in transactions {
+            let mut cells: Vec<String> = schema
+                .columns
+                .iter()
+                .map(|(field, _)| match (field, self.csv_amount_format) {
+                    // The separators would split the column unless quoted
+                    (CsvField::Amount, CsvAmountFormat::Thousands) => {
+                        format!(
+                            "\"{}\"",
+                            format_amount_grouped(t.amount, self.digit_grouping)
+                        )
+                    }
+                    _ => csv_field(&field.text(t, self.show_seconds)),
+                })
+                .collect();
+            if self.csv_include_memo {
+                cells.push(csv_field(t.memo.as_deref().unwrap_or("")));
+            }
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
         }
         csv
     }
 }
+
+/// Label used for transactions without an expense type
+pub const UNCATEGORIZED_LABEL: &str = "미분류";
+
+/// Stylesheet of the HTML export; rows don't split across printed pages
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; margin-bottom: 0.2em; }
+.meta { color: #666; margin-top: 0; }
+table { border-collapse: collapse; width: 100%; font-size: 0.9em; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }
+th { background: #f0f0f0; }
+tbody tr:nth-child(even) { background: #fafafa; }
+tfoot td { font-weight: bold; border-top: 2px solid #888; }
+.num { text-align: right; white-space: nowrap; }
+.img img { max-width: 160px; max-height: 240px; }
+@media print { body { margin: 0; } tr { break-inside: avoid; } }
+";
+
+/// Longest side of the thumbnails embedded in the HTML export
+const HTML_THUMBNAIL_MAX_SIDE: u32 = 320;
+
+/// Escape text for HTML element content
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Small JPEG of a receipt image as a `data:` URI; None when there is no decodable image
+fn thumbnail_data_uri(bytes: &[u8]) -> Option<String> {
+    use base64::Engine;
+
+    if bytes.is_empty() {
+        return None;
+    }
+    let img = image::load_from_memory(bytes).ok()?;
+    let thumb = img
+        .thumbnail(HTML_THUMBNAIL_MAX_SIDE, HTML_THUMBNAIL_MAX_SIDE)
+        .into_rgb8();
+    let mut jpeg = Vec::new();
+    image::DynamicImage::from(thumb)
+        .write_to(
+            &mut std::io::Cursor::new(&mut jpeg),
+            image::ImageFormat::Jpeg,
+        )
+        .ok()?;
+    Some(format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(jpeg)
+    ))
+}
+
+/// Rows of a CSV written by `AppState::to_csv` (any preset, with or without the memo
+/// column), to pick up earlier work. Imported rows have no image or OCR text and
+/// `CardFormat::Unknown`. sc-expense dates carry no year: the latest year in which the
+/// date exists and isn't in the future is assumed. A row with an unreadable date or
+/// amount is still imported, flagged for review.
+pub fn from_csv(text: &str) -> Result<Vec<CardTransaction>, String> {
+    let mut records = parse_csv_records(text.trim_start_matches('\u{FEFF}')).into_iter();
+    let headers = records.next().ok_or("CSV가 비어 있습니다")?;
+    let (schema, has_memo) = CsvSchema::from_headers(&headers)
+        .ok_or_else(|| format!("알 수 없는 CSV 머리글입니다: {}", headers.join(", ")))?;
+    let now = local_now();
+
+    let mut transactions = Vec::new();
+    for cells in records {
+        if cells.iter().all(|c| c.trim().is_empty()) {
+            continue;
+        }
+        let mut t = CardTransaction {
+            id: 0,
+            original_index: 0,
+            filename: String::new(),
+            datetime: unset_datetime(),
+            merchant: String::new(),
+            amount: 0,
+            parsed_amount: None,
+            raw_ocr_text: String::new(),
+            card_format: CardFormat::Unknown,
+            expense_type: None,
+            expense_auto: false,
+            needs_review: false,
+            review_notes: Vec::new(),
+            memo: None,
+            tags: Vec::new(),
+            foreign_amount: None,
+            status: None,
+            supply_amount: None,
+            vat_amount: None,
+            card_number: None,
+            approval_number: None,
+            business_number: None,
+            image_bytes: Default::default(),
+            extra_images: Vec::new(),
+            ocr_layout: None,
+            field_words: FieldWords::default(),
+        };
+        let non_empty = |cell: &str| (!cell.is_empty()).then(|| cell.to_string());
+        let (mut date, mut time) = (None, None);
+        let mut bad_date = None;
+        for ((field, _), cell) in schema.columns.iter().zip(&cells) {
+            let cell = cell.trim();
+            match field {
+                CsvField::Filename => t.filename = cell.to_string(),
+                CsvField::ShortDateTime => {
+                    // 02.29 only exists every 4 years (8 across a skipped century leap day)
+                    match (0..=8)
+                        .filter_map(|back| {
+                            let with_year = format!("{}.{}", now.year() - back, cell);
+                            NaiveDateTime::parse_from_str(&with_year, "%Y.%m.%d %H:%M").ok()
+                        })
+                        .find(|datetime| *datetime <= now)
+                    {
+                        Some(datetime) => t.datetime = datetime,
+                        None => bad_date = Some(cell),
+                    }
+                }
+                CsvField::Date => match NaiveDate::parse_from_str(cell, "%Y-%m-%d") {
+                    Ok(d) => date = Some(d),
+                    Err(_) => bad_date = Some(cell),
+                },
+                CsvField::Time => {
+                    time = NaiveTime::parse_from_str(cell, "%H:%M:%S")
+                        .or_else(|_| NaiveTime::parse_from_str(cell, "%H:%M"))
+                        .ok();
+                }
+                CsvField::Merchant => t.merchant = cell.to_string(),
+                // The column holds the expense type when one was set
+                CsvField::ExpenseOrMerchant => {
+                    t.merchant = cell.to_string();
+                    if expense::all_expense_labels().contains(&cell) {
+                        t.expense_type = Some(cell.to_string());
+                    }
+                }
+                CsvField::ExpenseType => t.expense_type = non_empty(cell),
+                CsvField::Amount => match parse_amount_input(cell) {
+                    Some(amount) => t.amount = amount,
+                    None => {
+                        t.needs_review = true;
+                        t.review_notes
+                            .push(format!("금액을 읽을 수 없습니다 ({})", cell));
+                    }
+                },
+                CsvField::Status => t.status = non_empty(cell),
+                CsvField::Tags => {
+                    t.tags = cell
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+            }
+        }
+        if let Some(date) = date {
+            t.datetime = date.and_time(time.unwrap_or_default());
+        }
+        if let Some(cell) = bad_date {
+            t.needs_review = true;
+            t.review_notes
+                .push(format!("날짜를 읽을 수 없습니다 ({})", cell));
+        }
+        if has_memo {
+            t.memo = cells
+                .get(schema.columns.len())
+                .and_then(|memo| non_empty(memo.trim()));
+        }
+        transactions.push(t);
+    }
+    Ok(transactions)
+}
+
+/// Split CSV text into records of unquoted fields (RFC 4180: `""` inside quotes
+/// is a quote, and quoted fields may span lines)
+fn parse_csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// `a` and `b` joined by `sep`, or just one of them when they are equal or one is blank
+fn join_distinct(a: &str, b: &str, sep: &str) -> String {
+    let (a, b) = (a.trim(), b.trim());
+    if a == b || b.is_empty() {
+        a.to_string()
+    } else if a.is_empty() {
+        b.to_string()
+    } else {
+        format!("{}{}{}", a, sep, b)
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A parsed row with the given fields, as OCR would produce it
+    fn txn(merchant: &str, amount: i64, datetime: &str) -> CardTransaction {
+        let mut t = parser::parse_receipt(
+            "a.png",
+            "",
+            &[],
+            None,
+            parser::DEFAULT_AMOUNT_CEILING,
+            DigitGrouping::Comma,
+            None,
+        );
+        t.merchant = merchant.to_string();
+        t.amount = amount;
+        t.datetime = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M").unwrap();
+        t
+    }
+
+    #[test]
+    fn csv_thousands_round_trip_for_every_grouping() {
+        for grouping in DigitGrouping::ALL {
+            let mut state = AppState::new();
+            state.csv_amount_format = CsvAmountFormat::Thousands;
+            state.digit_grouping = grouping;
+            state.transactions = vec![
+                txn("가게", 1_234_567, "2026-01-22 16:35"),
+                txn("환불", -45_000, "2026-01-23 09:00"),
+            ];
+            let rows = from_csv(&state.to_csv()).unwrap();
+            let amounts: Vec<i64> = rows.iter().map(|t| t.amount).collect();
+            assert_eq!(amounts, [1_234_567, -45_000], "{:?}", grouping);
+        }
+    }
+
+    #[test]
+    fn csv_export_then_import_keeps_merchant_amount_and_date() {
+        for schema in CsvSchema::PRESETS {
+            let mut state = AppState::new();
+            state.csv_schema = schema;
+            state.transactions = vec![
+                txn("\"큰\" 가게, 본점", 45_000, "2026-01-22 16:35"),
+                txn("환불", -1_200, "2025-12-31 23:59"),
+            ];
+            let rows = from_csv(&state.to_csv()).unwrap();
+            assert_eq!(rows.len(), 2, "{}", schema.name);
+            for (row, original) in rows.iter().zip(&state.transactions) {
+                assert_eq!(row.amount, original.amount, "{}", schema.name);
+                assert_eq!(row.merchant, original.merchant, "{}", schema.name);
+                // sc-expense writes dates without a year
+                assert_eq!(
+                    row.datetime.format("%m-%d %H:%M").to_string(),
+                    original.datetime.format("%m-%d %H:%M").to_string(),
+                    "{}",
+                    schema.name
+                );
+                assert!(row.image_bytes.is_empty());
+                assert_eq!(row.card_format, CardFormat::Unknown);
+            }
+        }
+    }
+
+    #[test]
+    fn csv_import_keeps_unreadable_rows_for_review() {
+        let rows =
+            from_csv("파일명,날짜,가맹점,금액\na.png,02.29 10:00,윤일,1000\nb.png,13.45 10:00,날짜,2000\nc.png,01.02 10:00,금액,abc\n")
+                .unwrap();
+        assert_eq!(rows.len(), 3);
+        // The latest past leap year, whatever year the test runs in
+        let leap = rows[0].datetime;
+        assert_eq!((leap.month(), leap.day()), (2, 29));
+        assert!(leap <= local_now() && !rows[0].needs_review);
+        assert!(rows[1].needs_review && rows[1].amount == 2000);
+        assert_eq!(rows[1].datetime, unset_datetime());
+        assert!(rows[2].needs_review && rows[2].amount == 0);
+        assert_eq!(rows[2].review_notes, ["금액을 읽을 수 없습니다 (abc)"]);
+    }
+
+    #[test]
+    fn amount_input_rejects_a_decimal_fraction() {
+        assert_eq!(parse_amount_input("45.000"), Some(45000));
+        assert_eq!(parse_amount_input("1 234 567"), Some(1_234_567));
+        assert_eq!(parse_amount_input("−1,200"), Some(-1200));
+        assert_eq!(parse_amount_input("45.5"), None);
+    }
+
+    #[test]
+    fn negative_amount_edit_round_trip() {
+        for amount in [-5_000, -500, 0, 1_234_567] {
+            assert_eq!(parse_amount_input(&format_amount(amount)), Some(amount));
+        }
+        assert_eq!(format_amount(-5_000), "-5,000");
+        assert_eq!(parse_amount_input("-5000"), Some(-5_000));
+
+        let mut state = AppState::new();
+        state.transactions = vec![
+            txn("가게", 12_000, "2026-01-22 16:35"),
+            txn("환불", -5_000, "2026-01-23 09:00"),
+        ];
+        assert_eq!(state.total_amount(), 7_000);
+    }
+
+    #[test]
+    fn undo_keeps_checks_on_the_same_rows() {
+        let mut state = AppState::new();
+        for merchant in ["a", "b", "c", "d"] {
+            state.push_transaction(txn(merchant, 1_000, "2026-01-22 16:35"));
+        }
+        state.selected_rows = [1, 3].into();
+        state.remove_transaction(0);
+        assert_eq!(state.selected_rows, [0, 2].into());
+        assert!(state.undo());
+        let checked: Vec<&str> = state
+            .selected_rows
+            .iter()
+            .map(|&i| state.transactions[i].merchant.as_str())
+            .collect();
+        assert_eq!(checked, ["b", "d"]);
+    }
+
+    #[test]
+    fn inline_merchant_edit_confirms_the_row() {
+        let mut state = AppState::new();
+        let mut t = txn("택시", 12_000, "2026-01-22 16:35");
+        t.expense_type = state.expense_rules.detect("택시").map(|r| r.label);
+        t.expense_auto = true;
+        t.needs_review = true;
+        t.review_notes = vec!["가맹점명을 찾을 수 없어 파일명으로 대신했습니다".into()];
+        state.push_transaction(t);
+        let edit = |text: &str| InlineEdit {
+            row: 0,
+            field: InlineField::Merchant,
+            text: text.to_string(),
+            focused: true,
+        };
+
+        // Clicking away without a change is not an undo step
+        assert!(state.commit_inline_edit(edit("택시")));
+        assert!(state.undo_stack.is_empty());
+
+        assert!(state.commit_inline_edit(edit("스타벅스")));
+        assert_eq!(state.undo_stack.len(), 1);
+        let t = &state.transactions[0];
+        assert_eq!(t.expense_type, None);
+        assert!(!t.needs_review && t.review_notes.is_empty());
+    }
+
+    #[test]
+    fn tax_summary_derives_the_missing_line() {
+        let mut state = AppState::new();
+        let mut supply_only = txn("공급가만", 11_000, "2026-01-22 16:35");
+        supply_only.supply_amount = Some(10_500);
+        let mut vat_only = txn("부가세만", 22_000, "2026-01-22 17:00");
+        vat_only.vat_amount = Some(2_500);
+        state.transactions = vec![
+            supply_only,
+            vat_only,
+            txn("없음", 1_100, "2026-01-23 09:00"),
+        ];
+        let summary = state.tax_summary();
+        assert_eq!(summary.supply, 10_500 + 19_500 + 1_000);
+        assert_eq!(summary.vat, 500 + 2_500 + 100);
+        assert_eq!(summary.estimated, 1);
+    }
+}
@@ -7,32 +7,158 @@
 //! Tesseract.js interop via wasm-bindgen
 //! Pattern: chama-optics js/heif_helper.js + image/heic_web.rs
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use wasm_bindgen::prelude::*;
 
+use crate::model::{OcrLayout, OcrLine, Word};
+
 #[wasm_bindgen(module = "/js/ocr_bridge.js")]
 extern "C" {
     #[wasm_bindgen(catch)]
-    async fn ocr_recognize(image_bytes: &[u8]) -> Result<JsValue, JsValue>;
+    async fn ocr_init() -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn ocr_recognize_words(image_bytes: &[u8]) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(catch)]
-    async fn open_file_picker(queue_callback: JsValue) -> Result<JsValue, JsValue>;
+    async fn ocr_detect_orientation(image_bytes: &[u8]) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn open_file_picker(accept: &str) -> Result<JsValue, JsValue>;
 
     fn download_file(data: &[u8], filename: &str, mime_type: &str);
 }
 
-/// Perform OCR on image bytes, returns recognized text
-pub async fn recognize_text(image_bytes: &[u8]) -> Result<String, String> {
-    let result = ocr_recognize(image_bytes)
+/// Set once `init` has finished, successfully or not
+static OCR_READY: AtomicBool = AtomicBool::new(false);
+
+/// Preload the Tesseract worker and language data.
+/// Spawned at startup so the first OCR run does not stall on it.
+pub async fn init() {
+    match ocr_init().await {
+        Ok(_) => log::info!("OCR worker ready"),
+        // recognize_words retries the worker, so a failure here still unblocks the UI
+        Err(e) => log::warn!("OCR worker preload failed: {:?}", e),
+    }
+    OCR_READY.store(true, Ordering::Release);
+}
+
+/// Whether `init` has finished
+pub fn is_ready() -> bool {
+    OCR_READY.load(Ordering::Acquire)
+}
+
+/// Perform OCR on image bytes, returning the recognized text and
+/// word bounding boxes (used for preview highlighting)
+pub async fn recognize_words(image_bytes: &[u8]) -> Result<(String, OcrLayout), String> {
+    let result = ocr_recognize_words(image_bytes)
         .await
         .map_err(|e| format!("OCR error: {:?}", e))?;
-    result
+    let text = get_field(&result, "text")?
         .as_string()
-        .ok_or_else(|| "OCR returned non-string result".into())
+        .ok_or_else(|| "OCR returned non-string text".to_string())?;
+    let width = get_field(&result, "width")?.as_f64().unwrap_or(0.0) as f32;
+    let height = get_field(&result, "height")?.as_f64().unwrap_or(0.0) as f32;
+
+    let words = get_boxes(&result, "words")?
+        .into_iter()
+        .map(|(text, bbox)| Word { text, bbox })
+        .collect();
+    let lines = get_boxes(&result, "lines")?
+        .into_iter()
+        .map(|(text, bbox)| OcrLine { text, bbox })
+        .collect();
+
+    Ok((
+        text,
+        OcrLayout {
+            width,
+            height,
+            words,
+            lines,
+        },
+    ))
+}
+
+/// `[{ text, x0, y0, x1, y1 }]` array field as (text, bbox) pairs
+fn get_boxes(obj: &JsValue, key: &str) -> Result<Vec<(String, [f32; 4])>, String> {
+    let array: js_sys::Array = get_field(obj, key)?
+        .dyn_into()
+        .map_err(|_| format!("Expected array of OCR {}", key))?;
+    let mut boxes = Vec::with_capacity(array.length() as usize);
+    for item in array.iter() {
+        let coord = |key: &str| -> Result<f32, String> {
+            Ok(get_field(&item, key)?.as_f64().unwrap_or(0.0) as f32)
+        };
+        boxes.push((
+            get_field(&item, "text")?.as_string().unwrap_or_default(),
+            [coord("x0")?, coord("y0")?, coord("x1")?, coord("y1")?],
+        ));
+    }
+    Ok(boxes)
+}
+
+fn get_field(obj: &JsValue, key: &str) -> Result<JsValue, String> {
+    js_sys::Reflect::get(obj, &key.into()).map_err(|_| format!("Missing {} field", key))
+}
+
+/// Detect page orientation via Tesseract OSD.
+/// Returns the clockwise rotation (0, 90, 180 or 270 degrees) that makes the text upright.
+pub async fn detect_orientation(image_bytes: &[u8]) -> Result<u32, String> {
+    let result = ocr_detect_orientation(image_bytes)
+        .await
+        .map_err(|e| format!("OSD error: {:?}", e))?;
+    let degrees = result
+        .as_f64()
+        .ok_or_else(|| "OSD returned non-number result".to_string())?;
+    Ok((degrees.round() as u32) % 360)
+}
+
+/// Rotate the image upright when OSD detects a sideways/upside-down capture.
+/// Falls back to the original bytes on any failure, since rotation is only an aid.
+pub async fn auto_rotate(image_bytes: Vec<u8>) -> Vec<u8> {
+    let degrees = match detect_orientation(&image_bytes).await {
+        Ok(d) => d,
+        Err(e) => {
+            log::warn!("Orientation detection failed: {}", e);
+            return image_bytes;
+        }
+    };
+    if degrees == 0 {
+        return image_bytes;
+    }
+
+    let Ok(img) = image::load_from_memory(&image_bytes) else {
+        return image_bytes;
+    };
+    let rotated = match degrees {
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        _ => return image_bytes,
+    };
+    let mut out = Vec::new();
+    match rotated.write_to(&mut std::io::Cursor::new(&mut out), image::ImageFormat::Png) {
+        Ok(()) => {
+            log::info!("Rotated image by {} degrees before OCR", degrees);
+            out
+        }
+        Err(e) => {
+            log::warn!("Failed to encode rotated image: {}", e);
+            image_bytes
+        }
+    }
 }
 
 /// Open file picker and return vec of (filename, bytes)
 pub async fn pick_files() -> Result<Vec<(String, Vec<u8>)>, String> {
-    let result = open_file_picker(JsValue::NULL)
+    pick_files_of_type("image/jpeg,image/png,image/jpg").await
+}
+
+/// Open file picker limited to `accept` (an `<input accept>` list)
+pub async fn pick_files_of_type(accept: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let result = open_file_picker(accept)
         .await
         .map_err(|e| format!("File picker error: {:?}", e))?;
 
@@ -10,137 +10,555 @@
 //! - 네이버 현대카드 (app screenshot, dark bg)
 //! - 카드앱 스크린샷 (매출전표 modal)
 
-use chrono::NaiveDateTime;
+use std::ops::Range;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use regex::Regex;
 
-use crate::model::{CardFormat, CardTransaction};
+use crate::model::{
+    CardFormat, CardTransaction, DigitGrouping, FieldWords, ForeignAmount, OcrLayout, OcrLine,
+    format_amount_grouped, local_now, unset_datetime,
+};
+
+/// Default for `amount_ceiling`: above 1억 원 a card receipt read is almost surely garbled
+pub const DEFAULT_AMOUNT_CEILING: i64 = 100_000_000;
+
+/// Byte range of the parsed text that a field was read from
+type Span = Range<usize>;
+
+/// Fields extracted by a single format parser, each with the `Span` it was read
+/// from. Date and amount keep their error so a partial parse can still become a row.
+struct ParsedFields {
+    datetime: Result<(NaiveDateTime, Span), String>,
+    merchant: Option<(String, Span)>,
+    amount: Result<(i64, Span), String>,
+}
+
+impl ParsedFields {
+    /// Number of fields that were found (used to pick the best fallback parse)
+    fn found_count(&self) -> usize {
+        self.datetime.is_ok() as usize + self.amount.is_ok() as usize + self.has_merchant() as usize
+    }
+
+    fn has_merchant(&self) -> bool {
+        self.merchant.as_ref().is_some_and(|(m, _)| !m.is_empty())
+    }
+}
+
+/// Range of `part` within `text`; `part` must be a slice of `text`
+fn span_of(text: &str, part: &str) -> Span {
+    let start = part.as_ptr() as usize - text.as_ptr() as usize;
+    start..start + part.len()
+}
+
+/// A merchant read from `line`, a slice of `text`
+fn located(text: &str, line: &str) -> (String, Span) {
+    (line.to_string(), span_of(text, line))
+}
 
-/// Detect format and parse OCR text into a CardTransaction
-pub fn parse_receipt(filename: &str, raw_text: &str) -> Result<CardTransaction, String> {
-    let format = detect_format(raw_text);
-    let (datetime, merchant, amount) = match format {
-        CardFormat::HanaCard => parse_hana_card(raw_text)?,
-        CardFormat::NaverHyundaiCard => parse_naver_hyundai(raw_text)?,
-        CardFormat::CardAppScreenshot => parse_card_app_screenshot(raw_text)?,
-        CardFormat::Unknown => parse_fallback(raw_text)?,
+/// Detect format and parse OCR text into a CardTransaction.
+///
+/// Never fails: fields that could not be found are left at their sentinel
+/// (`unset_datetime()`, amount 0, empty merchant) and the row is flagged
+/// `needs_review` with the reasons in `review_notes`.
+///
+/// `noise_tokens` are extra strings stripped from the merchant on top of the
+/// built-in list (see `clean_merchant_noise`).
+///
+/// When the text has no date, the date in the filename is used, then
+/// `capture_time` (the photo's EXIF capture time, see `preprocess::exif_datetime`).
+///
+/// An amount larger than `amount_ceiling` (either sign) is treated as a misread:
+/// the row gets 0원 and a review note quoting the value, grouped with `grouping`.
+///
+/// With the OCR `layout`, the row keeps it and records which words each field
+/// was read from (`field_words`).
+#[allow(clippy::too_many_arguments)]
+pub fn parse_receipt(
+    filename: &str,
+    raw_text: &str,
+    noise_tokens: &[String],
+    capture_time: Option<NaiveDateTime>,
+    amount_ceiling: i64,
+    grouping: DigitGrouping,
+    layout: Option<OcrLayout>,
+) -> CardTransaction {
+    // A full card number must never be stored or exported, not even in the raw text
+    let masked = mask_card_numbers_in_text(raw_text);
+    let raw_text = masked.as_str();
+    // Mostly non-Korean text (a receipt from abroad) can't match the Korean keyword formats
+    let scripts = ScriptMix::of(raw_text);
+    let foreign = scripts.is_mostly_foreign();
+    let format = if foreign {
+        CardFormat::Unknown
+    } else {
+        detect_format(raw_text)
     };
+    let mut fields = match format {
+        CardFormat::HanaCard => parse_hana_card(raw_text),
+        CardFormat::NaverHyundaiCard => parse_naver_hyundai(raw_text),
+        CardFormat::CardAppScreenshot => parse_card_app_screenshot(raw_text),
+        CardFormat::Unknown if foreign => parse_generic(raw_text),
+        CardFormat::Unknown => parse_fallback(raw_text),
+    };
+    // Cleaned after the format parser, since some use the raw merchant line as an anchor
+    if let Some((merchant, _)) = &mut fields.merchant {
+        *merchant = clean_merchant_noise(merchant, noise_tokens);
+    }
 
-    Ok(CardTransaction {
+    let mut review_notes = Vec::new();
+    if foreign {
+        review_notes.push(format!(
+            "한글이 거의 없어 일반 형식으로 읽었습니다 ({})",
+            scripts
+        ));
+    } else if format == CardFormat::Unknown && fields.found_count() < 3 {
+        review_notes.push(unknown_format_note(raw_text, &fields));
+    }
+    let mut datetime_span = None;
+    let datetime = match fields.datetime {
+        Ok((datetime, span)) => {
+            datetime_span = Some(span);
+            datetime
+        }
+        Err(e) => {
+            if let Some(datetime) = datetime_from_filename(filename) {
+                review_notes.push("거래일시가 없어 파일명의 날짜를 사용했습니다".to_string());
+                datetime
+            } else if let Some(datetime) = capture_time {
+                review_notes.push("거래일시가 없어 사진 촬영 시각을 사용했습니다".to_string());
+                datetime
+            } else {
+                review_notes.push(e);
+                unset_datetime()
+            }
+        }
+    };
+    // A runaway read ("99,999,999,999원") would swamp every total, so it is left out
+    let amount_span = fields.amount.as_ref().ok().map(|(_, span)| span.clone());
+    let checked_amount = fields.amount.and_then(|(amount, _)| {
+        if amount.abs() > amount_ceiling {
+            Err(format!(
+                "금액 {}원이 상한 {}원을 넘어 0원으로 두었습니다",
+                format_amount_grouped(amount, grouping),
+                format_amount_grouped(amount_ceiling, grouping)
+            ))
+        } else {
+            Ok(amount)
+        }
+    });
+    let parsed_amount = checked_amount.as_ref().ok().copied();
+    let amount = checked_amount.unwrap_or_else(|e| {
+        review_notes.push(e);
+        0
+    });
+    let (mut merchant, mut merchant_span) = fields
+        .merchant
+        .map_or((String::new(), None), |(m, span)| (m, Some(span)));
+    // A blank or one-letter merchant is useless in a report; the filename at least identifies the row
+    if merchant.chars().count() <= 1 {
+        merchant = merchant_from_filename(filename);
+        merchant_span = None;
+        review_notes.push("가맹점명을 찾을 수 없어 파일명으로 대신했습니다".to_string());
+    }
+    let field_words = layout
+        .as_ref()
+        .map(|layout| field_words(raw_text, layout, datetime_span, merchant_span, amount_span))
+        .unwrap_or_default();
+
+    CardTransaction {
+        id: 0,
+        original_index: 0,
         filename: filename.to_string(),
         datetime,
         merchant,
         amount,
+        parsed_amount,
         raw_ocr_text: raw_text.to_string(),
         card_format: format,
         expense_type: None,
-        image_bytes: Vec::new(),
-    })
+        expense_auto: false,
+        needs_review: !review_notes.is_empty(),
+        review_notes,
+        memo: None,
+        tags: Vec::new(),
+        status: extract_status(raw_text),
+        supply_amount: extract_amount_after_label(raw_text, "공급가액")
+            .ok()
+            .map(|(amount, _)| amount),
+        vat_amount: extract_amount_after_label(raw_text, "부가세")
+            .or_else(|_| extract_amount_after_label(raw_text, "부가가치세"))
+            .ok()
+            .map(|(amount, _)| amount),
+        card_number: extract_card_number(raw_text),
+        approval_number: extract_approval_number(raw_text),
+        business_number: extract_business_number(raw_text),
+        foreign_amount: extract_foreign_amount(raw_text),
+        image_bytes: Default::default(),
+        extra_images: Vec::new(),
+        ocr_layout: layout,
+        field_words,
+    }
 }
 
+/// Format whose signature keywords score highest by weight; ties go to the
+/// earlier format in `format_signatures`. A label shared by two layouts (e.g.
+/// 거래일시 in card-app screenshots) no longer decides on its own.
 fn detect_format(text: &str) -> CardFormat {
-    if text.contains("하나카드") || text.contains("거래일시") {
-        CardFormat::HanaCard
-    } else if text.contains("결제 정보")
-        || text.contains("결제정보")
-        || text.contains("현대카드")
-        || text.contains("거래 일자")
-        || text.contains("거래일자")
-    {
-        CardFormat::NaverHyundaiCard
-    } else if text.contains("카드이용내역")
-        || text.contains("매출전표")
-        || text.contains("상세 이용내역")
-    {
-        CardFormat::CardAppScreenshot
-    } else {
-        CardFormat::Unknown
+    let mut best = (CardFormat::Unknown, 0);
+    for (format, keywords, _) in format_signatures() {
+        let score: u32 = keywords
+            .iter()
+            .filter(|(k, _)| text.contains(k))
+            .map(|(_, w)| w)
+            .sum();
+        if score > best.1 {
+            best = (format, score);
+        }
     }
+    best.0
+}
+
+/// A format with its weighted signature keywords and date-shape regex
+type FormatSignature = (CardFormat, &'static [(&'static str, u32)], &'static str);
+
+/// Signature keywords and date-shape regexes per format. Weights pick the parser
+/// in `detect_format`: card brand names and screen titles count 3, field labels
+/// that other receipts may share count 1, and 0 only counts for `guess_format`.
+fn format_signatures() -> [FormatSignature; 3] {
+    [
+        (
+            CardFormat::HanaCard,
+            &[
+                ("하나카드", 3),
+                ("거래일시", 1),
+                ("승인금액", 0),
+                ("가맹점명", 0),
+                ("승인번호", 0),
+            ],
+            r"\d{4}\.\d{2}\.\d{2}\s*\d{2}:\d{2}:\d{2}",
+        ),
+        (
+            CardFormat::NaverHyundaiCard,
+            &[
+                ("결제 정보", 1),
+                ("결제정보", 1),
+                ("현대카드", 3),
+                ("거래 일자", 1),
+                ("거래일자", 1),
+                ("네이버", 0),
+                ("결제 카드", 0),
+            ],
+            r"\d{2}\.\s*\d{1,2}\.\s*\d{1,2}\D+\d{2}:\d{2}",
+        ),
+        (
+            CardFormat::CardAppScreenshot,
+            &[
+                ("카드이용내역", 3),
+                ("매출전표", 3),
+                ("상세 이용내역", 3),
+                // Also inside 거래일시/거래일자, so it never picks this format alone
+                ("거래일", 0),
+                ("공급가액", 0),
+                ("부가세", 0),
+            ],
+            r"\d{4}\.\d{2}\.\d{2}\s+\d{2}:\d{2}",
+        ),
+    ]
+}
+
+/// Score each known format (0.0–1.0) by the fraction of its signature
+/// keywords/regex pieces found in the text. Sorted best first; zero scores omitted.
+pub fn guess_format(text: &str) -> Vec<(CardFormat, f32)> {
+    let mut scores: Vec<(CardFormat, f32)> = format_signatures()
+        .into_iter()
+        .map(|(format, keywords, date_pattern)| {
+            let hits = keywords.iter().filter(|(k, _)| text.contains(k)).count()
+                + Regex::new(date_pattern).unwrap().is_match(text) as usize;
+            (format, hits as f32 / (keywords.len() + 1) as f32)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+/// Review note for an unrecognized format, naming the closest format and what was missing
+fn unknown_format_note(text: &str, fields: &ParsedFields) -> String {
+    let Some((format, _)) = guess_format(text).into_iter().next() else {
+        return "알 수 없는 영수증 형식입니다".to_string();
+    };
+    let mut missing = Vec::new();
+    if fields.datetime.is_err() {
+        missing.push("거래일시");
+    }
+    if fields.amount.is_err() {
+        missing.push("금액");
+    }
+    if !fields.has_merchant() {
+        missing.push("가맹점명");
+    }
+    format!(
+        "{} 형식과 유사하나 찾지 못한 항목: {}",
+        format,
+        missing.join(", ")
+    )
 }
 
 /// 하나카드 format:
 /// 거래일시 2026.01.22 16:35:39
 /// 승인금액 27,600 원
 /// 가맹점명 네이버파이낸셜(주)
-fn parse_hana_card(text: &str) -> Result<(NaiveDateTime, String, u64), String> {
-    let date_re =
-        Regex::new(r"거래일시\s+(\d{4})[.\s](\d{2})[.\s](\d{2})\s*(\d{2}):(\d{2}):?(\d{2})?")
-            .unwrap();
-    let datetime = if let Some(caps) = date_re.captures(text) {
-        let s = format!(
-            "{}-{}-{} {}:{}:{}",
-            &caps[1],
-            &caps[2],
-            &caps[3],
-            &caps[4],
-            &caps[5],
-            caps.get(6).map_or("00", |m| m.as_str())
-        );
-        NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| format!("날짜 파싱 오류: {}", e))?
+fn parse_hana_card(text: &str) -> ParsedFields {
+    let datetime = datetime_after_label(text, "거래일시")
+        .ok_or_else(|| "거래일시를 찾을 수 없습니다".to_string());
+
+    // Cancellation receipts say "거래구분 취소" and show the refunded sum as 취소금액
+    let cancelled = text.contains("취소금액")
+        || extract_text_after_label(text, "거래구분").is_some_and(|v| v.contains("취소"));
+    let amount = if cancelled {
+        extract_amount_after_label(text, "취소금액")
+            .or_else(|_| extract_amount_after_label(text, "승인금액"))
+            .or_else(|_| extract_first_amount(text))
+            .map(|(a, span)| (-a.abs(), span))
     } else {
-        return Err("거래일시를 찾을 수 없습니다".into());
+        extract_amount_after_label(text, "승인금액").or_else(|_| extract_first_amount(text))
     };
 
-    let amount =
-        extract_amount_after_label(text, "승인금액").or_else(|_| extract_first_amount(text))?;
+    let merchant = extract_wrapped_text_after_label(text, "가맹점명", HANA_LABELS)
+        .or_else(|| extract_merchant_before_amount(text, &[]).map(|line| located(text, line)));
 
-    let merchant = extract_text_after_label(text, "가맹점명")
-        .unwrap_or_else(|| extract_merchant_before_amount(text));
-
-    Ok((datetime, merchant, amount))
+    ParsedFields {
+        datetime,
+        merchant,
+        amount,
+    }
 }
 
+/// Field labels on the 하나카드 web receipt; a wrapped value stops at any of these
+const HANA_LABELS: &[&str] = &[
+    "카드번호",
+    "카드종류",
+    "거래일시",
+    "거래유형",
+    "거래구분",
+    "할부",
+    "승인번호",
+    "승인금액",
+    "승인상태",
+    "취소금액",
+    "공급가액",
+    "부가세",
+    "봉사료",
+    "합계",
+    "가맹점명",
+    "가맹점번호",
+    "가맹점 주소",
+    "사업자",
+    "대표자",
+    "주소",
+    "전화번호",
+];
+
 /// 네이버 현대카드 format:
 /// 해진구도일주유소일산지점
 /// 43,489원
 /// 거래 일자 26. 1. 31 · 14:59:27
-fn parse_naver_hyundai(text: &str) -> Result<(NaiveDateTime, String, u64), String> {
-    // Flexible date patterns for Naver Hyundai Card:
-    // OCR produces: "거래 일자      26. 3. 9 · 22:39:54"
-    // The middle dot (·) may be any of U+00B7, U+318D, U+2022, etc.
-    // Use \D+ (non-digit sequence) to skip any separator between date and time.
-    let date_patterns = [
-        // "거래 일자" followed by date and time, any non-digit separator
-        r"거래\s*일\s*자\s+(\d{2})\.\s*(\d{1,2})\.\s*(\d{1,2})\D+(\d{2}):(\d{2}):?(\d{2})?",
-        // Without "자" - OCR might drop it
-        r"거래\s*일\s+(\d{2})\.\s*(\d{1,2})\.\s*(\d{1,2})\D+(\d{2}):(\d{2}):?(\d{2})?",
-        // Fallback: any YY.M.DD followed by HH:MM:SS anywhere in text
-        r"(\d{2})\.\s*(\d{1,2})\.\s*(\d{1,2})\D+(\d{2}):(\d{2}):(\d{2})",
-    ];
+fn parse_naver_hyundai(text: &str) -> ParsedFields {
+    // Prefer the date after the "거래 일자" label; OCR can drop "자" or space the syllables
+    let datetime = datetime_after_label(text, r"거\s*래\s*일\s*자?")
+        .or_else(|| extract_datetime_flexible(text))
+        .ok_or_else(|| "거래 일자를 찾을 수 없습니다".to_string())
+        .and_then(reject_far_future);
 
-    let mut caps_opt = None;
-    for pat in &date_patterns {
-        let re = Regex::new(pat).unwrap();
-        if let Some(caps) = re.captures(text) {
-            caps_opt = Some(caps);
-            break;
+    // Skip 포인트/할인 lines, or their amount would end the merchant search early
+    let merchant = extract_merchant_before_amount(text, NAVER_NON_CHARGE_WORDS)
+        .map(|line| located(text, line));
+    // The charged amount is printed large right under the merchant; a smaller
+    // 포인트 적립/할인 amount may come first, so go by proximity before first-match
+    let anchor = merchant.as_ref().map_or("", |(m, _)| m.as_str());
+    let amount = extract_amount_nearest_line(text, anchor, NAVER_NON_CHARGE_WORDS)
+        .or_else(|_| extract_amount_after_label(text, "금액"))
+        .or_else(|_| extract_first_nonzero_amount(text))
+        .or_else(|_| extract_first_amount(text));
+
+    ParsedFields {
+        datetime,
+        merchant,
+        amount,
+    }
+}
+
+/// Noise OCR picks up around merchant names: ad tags and decorative symbols
+const MERCHANT_NOISE: &[&str] = &[
+    "[광고]", "(광고)", "[AD]", "(AD)", "★", "☆", "●", "○", "◆", "◇", "■", "□", "▶", "►", "♥", "※",
+    "✓",
+];
+
+/// Strip ad tags, decorative symbols, leading store numbers ("#12", "No.3") and
+/// any `extra` tokens from a merchant name, and turn full-width digits/spaces into ASCII
+pub fn clean_merchant_noise(merchant: &str, extra: &[String]) -> String {
+    let mut cleaned: String = merchant
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .collect();
+    for token in MERCHANT_NOISE
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str))
+        .filter(|t| !t.is_empty())
+    {
+        cleaned = cleaned.replace(token, " ");
+    }
+
+    let store_no = Regex::new(r"^(?:#|No\.?\s*)\d+\s+").unwrap();
+    let cleaned = store_no.replace(cleaned.trim(), "");
+    merge_spaced_syllables(&cleaned)
+}
+
+/// Rejoin a word OCR spelled out one syllable at a time ("스 타 벅 스" → "스타벅스").
+/// Only runs of three or more lone Hangul syllables are merged, so real two-word
+/// names and spaces next to digits or Latin letters are left alone.
+fn merge_spaced_syllables(text: &str) -> String {
+    /// Shorter runs are as likely to be real one-syllable words ("한 솥")
+    const MIN_RUN: usize = 3;
+
+    let is_syllable = |token: &str| {
+        let mut chars = token.chars();
+        matches!((chars.next(), chars.next()), (Some('가'..='힣'), None))
+    };
+    let mut words: Vec<String> = Vec::new();
+    let mut run: Vec<&str> = Vec::new();
+    let flush = |run: &mut Vec<&str>, words: &mut Vec<String>| {
+        if run.len() >= MIN_RUN {
+            words.push(run.concat());
+        } else {
+            words.extend(run.iter().map(|s| s.to_string()));
+        }
+        run.clear();
+    };
+    for token in text.split_whitespace() {
+        if is_syllable(token) {
+            run.push(token);
+        } else {
+            flush(&mut run, &mut words);
+            words.push(token.to_string());
         }
     }
+    flush(&mut run, &mut words);
+    words.join(" ")
+}
 
-    let datetime = if let Some(caps) = caps_opt {
-        let year = 2000 + caps[1].parse::<i32>().unwrap_or(26);
-        let s = format!(
-            "{}-{:02}-{:02} {}:{}:{}",
-            year,
-            caps[2].parse::<u32>().unwrap_or(1),
-            caps[3].parse::<u32>().unwrap_or(1),
-            &caps[4],
-            &caps[5],
-            caps.get(6).map_or("00", |m| m.as_str())
-        );
-        NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| format!("날짜 파싱 오류: {}", e))?
+/// Amount lines in 네이버 현대카드 screenshots that are not the charged amount
+const NAVER_NON_CHARGE_WORDS: &[&str] = &["적립", "포인트", "할인"];
+
+/// Non-zero amount on the line closest to `anchor` (a line containing it),
+/// ignoring lines with any of `skip_words`. Lines after the anchor win ties.
+/// Without an anchor match, the first non-skipped amount is used.
+fn extract_amount_nearest_line(
+    text: &str,
+    anchor: &str,
+    skip_words: &[&str],
+) -> Result<(i64, Span), String> {
+    let amount_re = amount_regex();
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let anchor_line = if anchor.is_empty() {
+        None
     } else {
-        return Err("거래 일자를 찾을 수 없습니다".into());
+        lines.iter().position(|l| l.contains(anchor))
     };
 
-    // Try labeled "금액" first, then first non-zero amount, then first amount
-    let amount = extract_amount_after_label(text, "금액")
-        .or_else(|_| extract_first_nonzero_amount(text))
-        .or_else(|_| extract_first_amount(text))?;
-    let merchant = extract_merchant_before_amount(text);
+    let candidates = lines.iter().enumerate().filter_map(|(i, line)| {
+        if skip_words.iter().any(|w| line.contains(w)) {
+            return None;
+        }
+        let caps = amount_re.captures(line)?;
+        let amount = parse_krw_amount(amount_digits(&caps)).ok()?;
+        (amount > 0).then(|| (i, amount, amount_span(&caps, span_of(text, line).start)))
+    });
+    let best = match anchor_line {
+        Some(a) => candidates.min_by_key(|&(i, _, _)| (i.abs_diff(a), i < a)),
+        None => candidates.min_by_key(|&(i, _, _)| i),
+    };
+    best.map(|(_, amount, span)| (amount, span))
+        .ok_or_else(|| "결제 금액을 찾을 수 없습니다".into())
+}
+
+/// Expand a 2-digit receipt year to the current century, stepping back a
+/// century if that would land more than a year ahead (e.g. "99" in 2026 → 1999)
+fn expand_two_digit_year(yy: i32) -> i32 {
+    let current = local_now().year();
+    let year = current - current % 100 + yy;
+    if year > current + 1 { year - 100 } else { year }
+}
+
+/// First date (and time, if present) in `text`, in any of the forms receipts and OCR
+/// produce: `2026.01.22 16:35:39`, `2026-1-22`, `2026 01 22 16:35`,
+/// `2026년 1월 22일`, `26. 1. 31 · 14:59:27`, `26/01/31`. Four-digit years are tried
+/// first, then two-digit years (see `expand_two_digit_year`); impossible dates are skipped.
+pub fn extract_datetime_flexible(text: &str) -> Option<(NaiveDateTime, Span)> {
+    // Date and time are split by spaces and/or one ·/-/: (the middle dot may be any
+    // of U+00B7, U+318D, U+2022, U+2219, U+30FB). Seconds are optional.
+    const TIME: &str =
+        r"(?:\.?\s*[·ㆍ•∙・\-:]?\s*(?P<h>\d{1,2}):(?P<min>\d{2})(?::(?P<s>\d{2}))?)?";
+    let patterns = [
+        format!(
+            r"(?P<y>(?:19|20)\d{{2}})(?:\s*[./-]\s*|\s*년\s*|\s+)(?P<m>\d{{1,2}})(?:\s*[./-]\s*|\s*월\s*|\s+)(?P<d>\d{{1,2}})(?:\s*일)?{TIME}"
+        ),
+        format!(
+            r"(?:^|\D)(?P<yy>\d{{2}})\s*[./-]\s*(?P<m>\d{{1,2}})\s*[./-]\s*(?P<d>\d{{1,2}}){TIME}"
+        ),
+    ];
+    patterns.iter().find_map(|pattern| {
+        Regex::new(pattern)
+            .unwrap()
+            .captures_iter(text)
+            .find_map(|caps| {
+                let num = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+                let year = match caps.name("y") {
+                    Some(y) => y.as_str().parse().ok()?,
+                    None => expand_two_digit_year(num("yy")? as i32),
+                };
+                let datetime = NaiveDate::from_ymd_opt(year, num("m")?, num("d")?)?.and_hms_opt(
+                    num("h").unwrap_or(0),
+                    num("min").unwrap_or(0),
+                    num("s").unwrap_or(0),
+                )?;
+                Some((datetime, caps.get(0)?.range()))
+            })
+    })
+}
+
+/// Date after the first match of `label` (a regex) that has one, looking at the
+/// rest of the label's line and the next line, where OCR may wrap the value
+fn datetime_after_label(text: &str, label: &str) -> Option<(NaiveDateTime, Span)> {
+    Regex::new(label).unwrap().find_iter(text).find_map(|m| {
+        let rest = &text[m.end()..];
+        let near_end = rest
+            .match_indices('\n')
+            .nth(1)
+            .map_or(rest.len(), |(i, _)| i);
+        let (datetime, span) = extract_datetime_flexible(&rest[..near_end])?;
+        Some((datetime, m.end() + span.start..m.end() + span.end))
+    })
+}
 
-    Ok((datetime, merchant, amount))
+/// Receipts can't be dated more than about a year ahead; such a date is OCR garbage
+fn reject_far_future(
+    (datetime, span): (NaiveDateTime, Span),
+) -> Result<(NaiveDateTime, Span), String> {
+    if datetime > local_now() + chrono::Duration::days(366) {
+        Err(format!(
+            "날짜가 너무 먼 미래입니다: {}",
+            datetime.format("%Y.%m.%d")
+        ))
+    } else {
+        Ok((datetime, span))
+    }
 }
 
 /// 카드앱 스크린샷 format:
@@ -148,30 +566,10 @@ fn parse_naver_hyundai(text: &str) -> Result<(NaiveDateTime, String, u64), Strin
 /// 스타한국물류
 /// 16,500원
 /// 거래일 2026.01.23 11:59
-fn parse_card_app_screenshot(text: &str) -> Result<(NaiveDateTime, String, u64), String> {
-    // Try "거래일" (without 시)
-    let date_re =
-        Regex::new(r"거래일\s+(\d{4})[.\s](\d{2})[.\s](\d{2})\s+(\d{2}):(\d{2})").unwrap();
-    // Also try "거래일" with full datetime
-    let date_re2 =
-        Regex::new(r"거래일\s+(\d{4})[.\s](\d{2})[.\s](\d{2})\s*(\d{2}):(\d{2}):?(\d{2})?")
-            .unwrap();
-
-    let datetime = if let Some(caps) = date_re.captures(text).or_else(|| date_re2.captures(text)) {
-        let s = format!(
-            "{}-{}-{} {}:{}:{}",
-            &caps[1],
-            &caps[2],
-            &caps[3],
-            &caps[4],
-            &caps[5],
-            caps.get(6).map_or("00", |m| m.as_str())
-        );
-        NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| format!("날짜 파싱 오류: {}", e))?
-    } else {
-        return Err("거래일을 찾을 수 없습니다".into());
-    };
+fn parse_card_app_screenshot(text: &str) -> ParsedFields {
+    // "거래일" also matches the "거래일시" some card apps print
+    let datetime =
+        datetime_after_label(text, "거래일").ok_or_else(|| "거래일을 찾을 수 없습니다".to_string());
 
     // For card app screenshots, prefer the total amount shown at the top of the
     // detail modal (right after merchant name), NOT 공급가액 which excludes 부가세.
@@ -179,29 +577,192 @@ fn parse_card_app_screenshot(text: &str) -> Result<(NaiveDateTime, String, u64),
     let amount = extract_first_amount_after_header(text, "상세 이용내역")
         .or_else(|_| extract_amount_after_label(text, "공급가액"))
         .or_else(|_| extract_first_nonzero_amount(text))
-        .or_else(|_| extract_first_amount(text))?;
+        .or_else(|_| extract_first_amount(text));
 
     let merchant = extract_merchant_from_card_detail(text)
         .or_else(|| extract_text_after_label(text, "상세 이용내역"))
-        .unwrap_or_else(|| extract_merchant_before_amount(text));
+        .or_else(|| extract_merchant_before_amount(text, &[]))
+        .map(|line| located(text, line));
+
+    ParsedFields {
+        datetime,
+        merchant,
+        amount,
+    }
+}
+
+/// Try every format parser and keep the one that found the most fields
+/// (earlier parsers win ties).
+fn parse_fallback(text: &str) -> ParsedFields {
+    [
+        parse_hana_card(text),
+        parse_naver_hyundai(text),
+        parse_card_app_screenshot(text),
+    ]
+    .into_iter()
+    .rev()
+    .max_by_key(ParsedFields::found_count)
+    .expect("at least one parser")
+}
+
+/// Receipts with little or no Korean (foreign branches): only language-independent
+/// patterns are used. The merchant falls back to the first line that reads like a name.
+fn parse_generic(text: &str) -> ParsedFields {
+    let datetime =
+        extract_datetime_flexible(text).ok_or_else(|| "거래일시를 찾을 수 없습니다".to_string());
+
+    let merchant = text
+        .lines()
+        .map(str::trim)
+        .find(|l| {
+            let letters = l.chars().filter(|c| c.is_alphabetic()).count();
+            letters >= 3 && letters * 2 >= l.chars().count()
+        })
+        .map(|line| located(text, line));
 
-    Ok((datetime, merchant, amount))
+    ParsedFields {
+        datetime,
+        merchant,
+        amount: extract_first_nonzero_amount(text),
+    }
+}
+
+/// Date (and time, if present) in a screenshot filename such as
+/// `Screenshot_20260122-163539.png` or `IMG_2026-01-22.jpg`
+fn datetime_from_filename(filename: &str) -> Option<NaiveDateTime> {
+    let re = Regex::new(
+        r"(?P<y>20\d{2})[-_.]?(?P<m>\d{2})[-_.]?(?P<d>\d{2})(?:[-_ ]?(?P<hh>\d{2})[-_.]?(?P<mm>\d{2})[-_.]?(?P<ss>\d{2}))?",
+    )
+    .unwrap();
+    let c = re.captures(filename)?;
+    let num = |name: &str| c.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+    NaiveDate::from_ymd_opt(c["y"].parse().ok()?, num("m")?, num("d")?)?.and_hms_opt(
+        num("hh").unwrap_or(0),
+        num("mm").unwrap_or(0),
+        num("ss").unwrap_or(0),
+    )
+}
+
+/// Filename prefixes that say nothing about the receipt
+const FILENAME_PREFIXES: &[&str] = &[
+    "screenshot",
+    "스크린샷",
+    "kakaotalk",
+    "img",
+    "image",
+    "photo",
+];
+
+/// Stand-in merchant label from a filename: extension and camera/screenshot
+/// prefixes removed, `_` turned into spaces (`Screenshot_스타벅스.png` → `스타벅스`)
+fn merchant_from_filename(filename: &str) -> String {
+    let stem = filename
+        .rsplit_once('.')
+        .map_or(filename, |(stem, _)| stem)
+        .trim();
+    let mut rest = stem;
+    for prefix in FILENAME_PREFIXES {
+        if let Some(head) = rest.get(..prefix.len())
+            && head.eq_ignore_ascii_case(prefix)
+        {
+            rest = &rest[prefix.len()..];
+            break;
+        }
+    }
+    let cleaned = rest.replace('_', " ").trim_matches([' ', '-']).to_string();
+    if cleaned.is_empty() {
+        stem.to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Character counts per script in OCR text
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScriptMix {
+    pub hangul: usize,
+    pub latin: usize,
+    /// Chinese characters and Japanese kana
+    pub cjk: usize,
+}
+
+impl ScriptMix {
+    /// Below this share of Hangul among letters, the text is treated as foreign
+    const MIN_HANGUL_RATIO: f32 = 0.1;
+    /// Too few letters to tell; keep the Korean formats
+    const MIN_LETTERS: usize = 20;
+
+    pub fn of(text: &str) -> Self {
+        let mut mix = Self::default();
+        for c in text.chars() {
+            match c {
+                '가'..='힣' | 'ㄱ'..='ㆎ' => mix.hangul += 1,
+                'a'..='z' | 'A'..='Z' | 'À'..='ɏ' => mix.latin += 1,
+                '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' => mix.cjk += 1,
+                _ => {}
+            }
+        }
+        mix
+    }
+
+    fn letters(&self) -> usize {
+        self.hangul + self.latin + self.cjk
+    }
+
+    pub fn is_mostly_foreign(&self) -> bool {
+        let letters = self.letters();
+        letters >= Self::MIN_LETTERS
+            && (self.hangul as f32) < letters as f32 * Self::MIN_HANGUL_RATIO
+    }
 }
 
-fn parse_fallback(text: &str) -> Result<(NaiveDateTime, String, u64), String> {
-    parse_hana_card(text)
-        .or_else(|_| parse_naver_hyundai(text))
-        .or_else(|_| parse_card_app_screenshot(text))
-        .map_err(|_| "알 수 없는 영수증 형식입니다".into())
+impl std::fmt::Display for ScriptMix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letters = self.letters().max(1) as f32;
+        let pct = |n: usize| (n as f32 * 100.0 / letters).round() as u32;
+        write!(
+            f,
+            "한글 {}% · 라틴 {}% · 한자/가나 {}%",
+            pct(self.hangul),
+            pct(self.latin),
+            pct(self.cjk)
+        )
+    }
 }
 
 // --- Helper functions ---
 
+/// KRW amount in any of the forms receipts use: "45,000원", "45,000원정",
+/// "₩45,000", "KRW 45,000", "45,000.00원", "45.000원" or with Korean units ("4만 5천원").
+/// The amount text lands in group `prefixed` or `suffixed`.
+const AMOUNT_PATTERN: &str = r"(?:(?:₩|￦|KRW)\s*(?P<prefixed>\d[\d,]*(?:\.\d+)*)|(?P<suffixed>(?:\d[\d,]*\s*[억만천백십]\s*)*\d[\d,]*(?:\.\d+)*(?:\s*[억만천백십])*)\s*원)";
+
+fn amount_regex() -> Regex {
+    Regex::new(AMOUNT_PATTERN).unwrap()
+}
+
+/// Digits of an `AMOUNT_PATTERN` match, whichever form matched
+fn amount_digits<'t>(caps: &regex::Captures<'t>) -> &'t str {
+    caps.name("prefixed")
+        .or_else(|| caps.name("suffixed"))
+        .map_or("", |m| m.as_str())
+}
+
+/// Span of the digits of an `AMOUNT_PATTERN` match in a haystack that starts at
+/// `offset` of the parsed text
+fn amount_span(caps: &regex::Captures, offset: usize) -> Span {
+    let range = caps
+        .name("prefixed")
+        .or_else(|| caps.name("suffixed"))
+        .map_or(0..0, |m| m.range());
+    offset + range.start..offset + range.end
+}
+
 /// Extract the first non-zero amount that appears after a given header line.
 /// Used for card app screenshots to get the total amount from the modal,
 /// not the 공급가액 breakdown.
-fn extract_first_amount_after_header(text: &str, header: &str) -> Result<u64, String> {
-    let amount_re = Regex::new(r"([\d,]+)\s*원").unwrap();
+fn extract_first_amount_after_header(text: &str, header: &str) -> Result<(i64, Span), String> {
+    let amount_re = amount_regex();
     let mut found_header = false;
     for line in text.lines() {
         let trimmed = line.trim();
@@ -214,68 +775,127 @@ fn extract_first_amount_after_header(text: &str, header: &str) -> Result<u64, St
         }
         // Look for the first amount line after the header (this is the total)
         if let Some(caps) = amount_re.captures(trimmed)
-            && let Ok(amount) = parse_krw_amount(&caps[1])
+            && let Ok(amount) = parse_krw_amount(amount_digits(&caps))
             && amount > 0
         {
-            return Ok(amount);
+            return Ok((amount, amount_span(&caps, span_of(text, trimmed).start)));
         }
     }
     Err(format!("'{}' 이후 금액을 찾을 수 없습니다", header))
 }
 
-fn extract_amount_after_label(text: &str, label: &str) -> Result<u64, String> {
-    let pattern = format!(r"{}\s+([\d,]+)\s*원", regex::escape(label));
+fn extract_amount_after_label(text: &str, label: &str) -> Result<(i64, Span), String> {
+    let pattern = format!(r"{}\s+{}", regex::escape(label), AMOUNT_PATTERN);
     let re = Regex::new(&pattern).unwrap();
     if let Some(caps) = re.captures(text) {
-        parse_krw_amount(&caps[1])
+        parse_krw_amount(amount_digits(&caps)).map(|amount| (amount, amount_span(&caps, 0)))
     } else {
         Err(format!("'{}' 뒤에서 금액을 찾을 수 없습니다", label))
     }
 }
 
-fn extract_first_amount(text: &str) -> Result<u64, String> {
-    let re = Regex::new(r"([\d,]+)\s*원").unwrap();
-    if let Some(caps) = re.captures(text) {
-        parse_krw_amount(&caps[1])
+fn extract_first_amount(text: &str) -> Result<(i64, Span), String> {
+    if let Some(caps) = amount_regex().captures(text) {
+        parse_krw_amount(amount_digits(&caps)).map(|amount| (amount, amount_span(&caps, 0)))
     } else {
         Err("금액을 찾을 수 없습니다".into())
     }
 }
 
-fn extract_first_nonzero_amount(text: &str) -> Result<u64, String> {
-    let re = Regex::new(r"([\d,]+)\s*원").unwrap();
-    for caps in re.captures_iter(text) {
-        if let Ok(amount) = parse_krw_amount(&caps[1])
+fn extract_first_nonzero_amount(text: &str) -> Result<(i64, Span), String> {
+    for caps in amount_regex().captures_iter(text) {
+        if let Ok(amount) = parse_krw_amount(amount_digits(&caps))
             && amount > 0
         {
-            return Ok(amount);
+            return Ok((amount, amount_span(&caps, 0)));
         }
     }
     Err("0이 아닌 금액을 찾을 수 없습니다".into())
 }
 
-fn parse_krw_amount(s: &str) -> Result<u64, String> {
-    let cleaned: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
+fn parse_krw_amount(s: &str) -> Result<i64, String> {
+    // Spelled-out units ("4만5천") would read as "45" digit-by-digit
+    if s.contains(KOREAN_UNITS) {
+        return parse_korean_units(s)
+            .and_then(|v| i64::try_from(v).ok())
+            .ok_or_else(|| format!("금액 파싱 오류: {}", s));
+    }
+    // Won has no subunit: "45,000.00" is 45000, so a fraction (one or two digits
+    // after the last '.') is dropped. Otherwise '.' is a thousands separator as in
+    // "45.000원" or "1.234.000원". Foreign amounts keep fractions, see `extract_foreign_amount`.
+    let whole = match s.rsplit_once('.') {
+        Some((int, frac))
+            if (1..=2).contains(&frac.chars().filter(char::is_ascii_digit).count()) =>
+        {
+            int
+        }
+        _ => s,
+    };
+    let cleaned: String = whole.chars().filter(|c| c.is_ascii_digit()).collect();
     cleaned
-        .parse::<u64>()
+        .parse::<i64>()
         .map_err(|e| format!("금액 파싱 오류: {}", e))
 }
 
-fn extract_text_after_label(text: &str, label: &str) -> Option<String> {
+const KOREAN_UNITS: [char; 5] = ['억', '만', '천', '백', '십'];
+
+/// Parse an amount written with Korean number units: "4만 5천원" → 45000,
+/// "1억 2천만" → 120000000, "3천500원" → 3500. Digits may carry commas.
+/// Returns None without any unit, or on a malformed/overflowing amount.
+pub fn parse_korean_units(s: &str) -> Option<u64> {
+    let mut total: u64 = 0; // completed 억/만 groups
+    let mut section: u64 = 0; // value below 만 within the current group
+    let mut num: Option<u64> = None; // digits read since the last unit
+    let mut saw_unit = false;
+
+    for c in s.chars() {
+        match c {
+            '0'..='9' => {
+                let d = c as u64 - '0' as u64;
+                num = Some(num.unwrap_or(0).checked_mul(10)?.checked_add(d)?);
+            }
+            ',' | ' ' | '원' | '정' => {}
+            '천' | '백' | '십' => {
+                let unit = match c {
+                    '천' => 1000,
+                    '백' => 100,
+                    _ => 10,
+                };
+                section = section.checked_add(num.take().unwrap_or(1).checked_mul(unit)?)?;
+                saw_unit = true;
+            }
+            '만' | '억' => {
+                let unit = if c == '만' { 10_000 } else { 100_000_000 };
+                let group = section + num.take().unwrap_or(0);
+                let group = if group == 0 { 1 } else { group };
+                total = total.checked_add(group.checked_mul(unit)?)?;
+                section = 0;
+                saw_unit = true;
+            }
+            _ => return None,
+        }
+    }
+    if !saw_unit {
+        return None;
+    }
+    total.checked_add(section)?.checked_add(num.unwrap_or(0))
+}
+
+fn extract_text_after_label<'t>(text: &'t str, label: &str) -> Option<&'t str> {
     for (i, line) in text.lines().enumerate() {
         if line.contains(label) {
             // Value on same line after label
             if let Some(after) = line.split(label).nth(1) {
                 let trimmed = after.trim();
                 if !trimmed.is_empty() && trimmed != "X" && trimmed != "x" {
-                    return Some(trimmed.to_string());
+                    return Some(trimmed);
                 }
             }
             // Or on the next line
             if let Some(next) = text.lines().nth(i + 1) {
                 let trimmed = next.trim();
                 if !trimmed.is_empty() {
-                    return Some(trimmed.to_string());
+                    return Some(trimmed);
                 }
             }
         }
@@ -283,10 +903,191 @@ fn extract_text_after_label(text: &str, label: &str) -> Option<String> {
     None
 }
 
+/// Currencies recognized in 현지승인금액 lines
+const FOREIGN_CURRENCIES: &str = "USD|JPY|EUR|CNY";
+
+/// Currency symbols accepted before an amount, with the code they stand for.
+/// ¥ is read as yen; yuan receipts print CNY or 元.
+const CURRENCY_SYMBOLS: [(&str, &str); 4] =
+    [("US$", "USD"), ("$", "USD"), ("¥", "JPY"), ("€", "EUR")];
+
+/// Original-currency amount of an overseas purchase ("현지승인금액 12.50 USD",
+/// "USD 12.50" or "$12.50"); the 현지승인금액 line wins over any other currency
+/// mention. Codes must stand alone, so words that merely contain one don't match.
+fn extract_foreign_amount(text: &str) -> Option<ForeignAmount> {
+    let symbols: Vec<String> = CURRENCY_SYMBOLS
+        .iter()
+        .map(|(symbol, _)| regex::escape(symbol))
+        .collect();
+    let re = Regex::new(&format!(
+        r"(?:(?:^|[^A-Za-z])(?P<cur1>{c}|{s})\s*(?P<amt1>\d[\d,]*(?:\.\d+)?)|(?P<amt2>\d[\d,]*(?:\.\d+)?)\s*(?P<cur2>{c})(?:$|[^A-Za-z]))",
+        c = FOREIGN_CURRENCIES,
+        s = symbols.join("|")
+    ))
+    .unwrap();
+    let parse = |line: &str| {
+        let caps = re.captures(line)?;
+        let currency = caps.name("cur1").or_else(|| caps.name("cur2"))?.as_str();
+        let currency = CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| *symbol == currency)
+            .map_or(currency, |(_, code)| code);
+        let amount = caps.name("amt1").or_else(|| caps.name("amt2"))?.as_str();
+        Some(ForeignAmount {
+            currency: currency.to_string(),
+            amount: amount.replace(',', "").parse().ok()?,
+        })
+    };
+    text.lines()
+        .filter(|l| l.contains("현지승인금액"))
+        .find_map(parse)
+        .or_else(|| text.lines().find_map(parse))
+}
+
+/// Mask every digit of a card number except the last four, keeping separators:
+/// `1234-5678-9012-3456` → `****-****-****-3456`. Already-masked digits stay masked.
+pub fn mask_card_number(number: &str) -> String {
+    let digits = number.chars().filter(char::is_ascii_digit).count();
+    let mut seen = 0;
+    number
+        .chars()
+        .map(|c| {
+            if !c.is_ascii_digit() {
+                return c;
+            }
+            seen += 1;
+            if seen + 4 > digits { c } else { '*' }
+        })
+        .collect()
+}
+
+/// Mask anything in OCR text that looks like a full card number (14–19 digits,
+/// optionally grouped with spaces or hyphens)
+pub fn mask_card_numbers_in_text(text: &str) -> String {
+    let re = Regex::new(r"\b\d{4}(?:[- ]?\d{2,6}){2,4}\b").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| {
+        let m = &caps[0];
+        let digits = m.chars().filter(char::is_ascii_digit).count();
+        if (14..=19).contains(&digits) {
+            mask_card_number(m)
+        } else {
+            m.to_string()
+        }
+    })
+    .into_owned()
+}
+
+/// Card number from the 카드번호 field, masked to the last four digits
+fn extract_card_number(text: &str) -> Option<String> {
+    let value = extract_text_after_label(text, "카드번호")?;
+    let number: String = value
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '*' | '-' | ' '))
+        .collect();
+    let number = number.trim();
+    (number.chars().filter(|c| *c != '-' && *c != ' ').count() >= 8)
+        .then(|| mask_card_number(number))
+}
+
+/// Approval number from the 승인번호 field
+fn extract_approval_number(text: &str) -> Option<String> {
+    let re = Regex::new(r"승인\s?번호\s*:?\s*(\d{6,12})").unwrap();
+    re.captures(text).map(|c| c[1].to_string())
+}
+
+/// 사업자등록번호 as `123-45-67890`: the number after a 사업자(등록)번호 label,
+/// else the first `3-2-5` digit group anywhere in the text
+fn extract_business_number(text: &str) -> Option<String> {
+    const NUMBER: &str = r"(\d{3})\s?-\s?(\d{2})\s?-\s?(\d{5})";
+    let labeled = Regex::new(&format!(r"사업자\s?(?:등록)?\s?번호\s*:?\s*{}", NUMBER)).unwrap();
+    let bare = Regex::new(&format!(r"(?:^|[^\d-]){}(?:$|[^\d-])", NUMBER)).unwrap();
+    let caps = labeled.captures(text).or_else(|| bare.captures(text))?;
+    Some(format!("{}-{}-{}", &caps[1], &caps[2], &caps[3]))
+}
+
+/// Check digit of a 사업자등록번호 (weights 1,3,7,1,3,7,1,3,5; the 9th digit's
+/// product also adds its tens). A mismatch usually means an OCR misread.
+pub fn is_valid_business_number(number: &str) -> bool {
+    const WEIGHTS: [u32; 9] = [1, 3, 7, 1, 3, 7, 1, 3, 5];
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 10 {
+        return false;
+    }
+    let mut sum: u32 = digits.iter().zip(WEIGHTS).map(|(d, w)| d * w).sum();
+    sum += digits[8] * 5 / 10;
+    (10 - sum % 10) % 10 == digits[9]
+}
+
+/// Approval status ("정상", "취소", "매입", ...) from the 승인상태/거래상태 field,
+/// or 거래구분 on 하나카드 cancellation receipts
+fn extract_status(text: &str) -> Option<String> {
+    // Longer words first so "승인취소" isn't read as "승인"
+    const STATUS_WORDS: &[&str] = &[
+        "승인취소",
+        "매입취소",
+        "부분취소",
+        "취소",
+        "매입",
+        "정상",
+        "승인",
+    ];
+    ["승인상태", "거래상태", "거래구분"]
+        .iter()
+        .find_map(|label| {
+            let value = extract_text_after_label(text, label)?;
+            STATUS_WORDS
+                .iter()
+                .find(|w| value.contains(*w))
+                .map(|w| w.to_string())
+        })
+}
+
+/// Like `extract_text_after_label`, but joins continuation lines of a value that
+/// wrapped (e.g. "네이버파이낸셜(주) /" + "스마트스토어"). Stops at a blank line,
+/// another known label, or an amount line so the next field is never swallowed.
+fn extract_wrapped_text_after_label(
+    text: &str,
+    label: &str,
+    labels: &[&str],
+) -> Option<(String, Span)> {
+    /// Wrapped names rarely span more than three lines; anything longer is another field
+    const MAX_CONTINUATION_LINES: usize = 2;
+
+    let first = extract_text_after_label(text, label)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let label_line = lines.iter().position(|l| l.contains(label))?;
+    // Skip the line the value itself was read from
+    let value_line = lines[label_line..]
+        .iter()
+        .position(|l| l.contains(first))
+        .map(|p| label_line + p)?;
+
+    let amount_re = amount_regex();
+    let mut merged = first.to_string();
+    let mut span = span_of(text, first);
+    for line in lines
+        .iter()
+        .skip(value_line + 1)
+        .take(MAX_CONTINUATION_LINES)
+    {
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || labels.iter().any(|l| trimmed.contains(l))
+            || amount_re.is_match(trimmed)
+        {
+            break;
+        }
+        merged.push(' ');
+        merged.push_str(trimmed);
+        span.end = span_of(text, trimmed).end;
+    }
+    Some((merged, span))
+}
+
 /// Extract merchant name from "상세 이용내역" popup in card app screenshots.
 /// Handles two-column OCR where right-panel fields (거래구분, 승인번호 etc.)
 /// may appear interspersed between the header and merchant name.
-fn extract_merchant_from_card_detail(text: &str) -> Option<String> {
+fn extract_merchant_from_card_detail(text: &str) -> Option<&str> {
     const NON_MERCHANT: &[&str] = &[
         "거래구분",
         "승인번호",
@@ -307,7 +1108,7 @@ fn extract_merchant_from_card_detail(text: &str) -> Option<String> {
         "매출전표",
         "구글페이",
     ];
-    let amount_re = Regex::new(r"[\d,]+\s*원").unwrap();
+    let amount_re = amount_regex();
 
     let mut found_header = false;
     for line in text.lines() {
@@ -348,13 +1149,15 @@ fn extract_merchant_from_card_detail(text: &str) -> Option<String> {
             continue;
         }
 
-        return Some(trimmed.to_string());
+        return Some(trimmed);
     }
     None
 }
 
-fn extract_merchant_before_amount(text: &str) -> String {
-    let amount_re = Regex::new(r"[\d,]+\s*원").unwrap();
+/// Last name-like line before the first amount line. Lines containing any of
+/// `skip_words` are passed over entirely.
+fn extract_merchant_before_amount<'t>(text: &'t str, skip_words: &[&str]) -> Option<&'t str> {
+    let amount_re = amount_regex();
     let skip_patterns = [
         "카드이용내역",
         "매출전표",
@@ -392,10 +1195,10 @@ fn extract_merchant_before_amount(text: &str) -> String {
         "사업자 등록번호",
         "업종",
     ];
-    let mut candidate = String::new();
+    let mut candidate = None;
     for line in text.lines() {
         let trimmed = line.trim();
-        if trimmed.is_empty() {
+        if trimmed.is_empty() || skip_words.iter().any(|w| trimmed.contains(w)) {
             continue;
         }
         if amount_re.is_match(trimmed) {
@@ -405,8 +1208,438 @@ fn extract_merchant_before_amount(text: &str) -> String {
             continue;
         }
         if trimmed.len() > 1 {
-            candidate = trimmed.to_string();
+            candidate = Some(trimmed);
         }
     }
     candidate
 }
+
+/// x position of the gap between two text columns, if the lines form a two-column
+/// layout: at least two lines on each side, few lines crossing the gap, and the two
+/// columns' rows not lined up. Row-aligned columns are a label/value table, which
+/// Tesseract already reads in the right (row by row) order.
+pub fn column_gutter(lines: &[OcrLine]) -> Option<f32> {
+    if lines.len() < 4 {
+        return None;
+    }
+    let left = lines
+        .iter()
+        .map(|l| l.bbox[0])
+        .fold(f32::INFINITY, f32::min);
+    let right = lines
+        .iter()
+        .map(|l| l.bbox[2])
+        .fold(f32::NEG_INFINITY, f32::max);
+    let width = right - left;
+
+    // A gap starts right after some line's right edge; try each one in the middle band
+    let mut best: Option<(usize, f32)> = None;
+    for gap in lines.iter().map(|l| l.bbox[2]) {
+        if gap < left + width * 0.2 || gap > left + width * 0.8 {
+            continue;
+        }
+        let (lhs, rhs) = split_columns(lines, gap);
+        if lhs.len() < 2 || rhs.len() < 2 {
+            continue;
+        }
+        let crossing = lines.len() - lhs.len() - rhs.len();
+        if best.is_none_or(|(c, _)| crossing < c) {
+            best = Some((crossing, gap));
+        }
+    }
+    let (crossing, gap) = best?;
+    if crossing * 3 > lines.len() {
+        return None;
+    }
+
+    let (lhs, rhs) = split_columns(lines, gap);
+    let same_row = |a: &OcrLine, b: &OcrLine| {
+        let (ca, cb) = ((a.bbox[1] + a.bbox[3]) / 2.0, (b.bbox[1] + b.bbox[3]) / 2.0);
+        (ca - cb).abs() < (a.bbox[3] - a.bbox[1]).max(b.bbox[3] - b.bbox[1]) / 2.0
+    };
+    let aligned = rhs
+        .iter()
+        .filter(|r| lhs.iter().any(|l| same_row(l, r)))
+        .count();
+    (aligned * 2 <= rhs.len()).then_some(gap)
+}
+
+/// Lines entirely left of `gap` and entirely right of it
+fn split_columns(lines: &[OcrLine], gap: f32) -> (Vec<&OcrLine>, Vec<&OcrLine>) {
+    (
+        lines.iter().filter(|l| l.bbox[2] <= gap).collect(),
+        lines.iter().filter(|l| l.bbox[0] > gap).collect(),
+    )
+}
+
+/// Rebuild reading order from OCR line boxes: top to bottom, and in a two-column
+/// layout the left column before the right one. Lines spanning both columns
+/// (headers, totals) split the page into sections that are read in turn.
+pub fn reflow_lines(lines: &[OcrLine]) -> String {
+    let mut sorted: Vec<&OcrLine> = lines.iter().collect();
+    sorted.sort_by(|a, b| a.bbox[1].total_cmp(&b.bbox[1]));
+    let Some(gap) = column_gutter(lines) else {
+        return sorted
+            .iter()
+            .map(|l| l.text.trim())
+            .collect::<Vec<_>>()
+            .join("\n");
+    };
+
+    let mut out: Vec<&str> = Vec::new();
+    let mut right_col: Vec<&str> = Vec::new();
+    for line in sorted {
+        if line.bbox[2] <= gap {
+            out.push(line.text.trim());
+        } else if line.bbox[0] > gap {
+            right_col.push(line.text.trim());
+        } else {
+            // Spanning line: finish this section's right column first
+            out.append(&mut right_col);
+            out.push(line.text.trim());
+        }
+    }
+    out.append(&mut right_col);
+    out.join("\n")
+}
+
+/// Which OCR words each field was read from: those whose place in the parsed
+/// text overlaps the field's span
+fn field_words(
+    text: &str,
+    layout: &OcrLayout,
+    datetime: Option<Span>,
+    merchant: Option<Span>,
+    amount: Option<Span>,
+) -> FieldWords {
+    let words = word_spans(text, layout);
+    let overlapping = |field: Option<Span>| -> Vec<usize> {
+        let Some(field) = field else {
+            return Vec::new();
+        };
+        words
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| {
+                w.as_ref()
+                    .is_some_and(|w| w.start < field.end && field.start < w.end)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    };
+    FieldWords {
+        datetime: overlapping(datetime),
+        merchant: overlapping(merchant),
+        amount: overlapping(amount),
+    }
+}
+
+/// Where each OCR word sits in the parsed text. A word belongs to the OCR line
+/// whose box holds its center, and each line appears whole in the text whether
+/// or not it was reflowed, so line order doesn't matter. Words not found stay None.
+fn word_spans(text: &str, layout: &OcrLayout) -> Vec<Option<Span>> {
+    // Each text line is claimed by one OCR line, so repeated lines map in order
+    let mut text_lines: Vec<Option<&str>> = text.lines().map(|l| Some(l.trim())).collect();
+    let mut spans = vec![None; layout.words.len()];
+    for line in &layout.lines {
+        // The parsed text has card numbers masked
+        let content = mask_card_numbers_in_text(line.text.trim());
+        if content.is_empty() {
+            continue;
+        }
+        let Some(found) = text_lines
+            .iter_mut()
+            .find(|l| **l == Some(content.as_str()))
+            .and_then(Option::take)
+        else {
+            continue;
+        };
+        let start = span_of(text, found).start;
+        let [x0, y0, x1, y1] = line.bbox;
+        let mut cursor = 0;
+        for (i, word) in layout.words.iter().enumerate() {
+            let cx = (word.bbox[0] + word.bbox[2]) / 2.0;
+            let cy = (word.bbox[1] + word.bbox[3]) / 2.0;
+            let word_text = word.text.trim();
+            if spans[i].is_some()
+                || word_text.is_empty()
+                || cx < x0
+                || cx > x1
+                || cy < y0
+                || cy > y1
+            {
+                continue;
+            }
+            if let Some(p) = found[cursor..].find(word_text) {
+                let at = start + cursor + p;
+                spans[i] = Some(at..at + word_text.len());
+                cursor += p + word_text.len();
+            }
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{OcrLine, Word};
+
+    /// One OCR line per text line, words 10px apart
+    fn layout_of(text: &str) -> OcrLayout {
+        let mut words = Vec::new();
+        let mut lines = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            let y = row as f32 * 20.0;
+            for (col, word) in line.split_whitespace().enumerate() {
+                let x = col as f32 * 100.0;
+                words.push(Word {
+                    text: word.to_string(),
+                    bbox: [x, y, x + 90.0, y + 15.0],
+                });
+            }
+            lines.push(OcrLine {
+                text: line.to_string(),
+                bbox: [0.0, y, 1000.0, y + 15.0],
+            });
+        }
+        OcrLayout {
+            width: 1000.0,
+            height: 20.0 * lines.len() as f32,
+            words,
+            lines,
+        }
+    }
+
+    /// Parse OCR text without a layout, with the default ceiling and grouping
+    fn parse(text: &str) -> CardTransaction {
+        parse_receipt(
+            "a.png",
+            text,
+            &[],
+            None,
+            DEFAULT_AMOUNT_CEILING,
+            DigitGrouping::Comma,
+            None,
+        )
+    }
+
+    /// A 하나카드 receipt with the given 승인금액 line value
+    fn hana(amount: &str) -> CardTransaction {
+        parse(&format!(
+            "하나카드\n거래일시 2026.01.22 16:35:39\n승인금액 {}\n가맹점명 X가게\n",
+            amount
+        ))
+    }
+
+    #[test]
+    fn field_words_come_from_where_each_field_was_parsed() {
+        // The amount also appears in the merchant name; only the 승인금액 word is the amount
+        let text =
+            "하나카드\n가맹점명 5,000원 마트\n거래일시 2026.01.22 16:35:39\n승인금액 5,000원\n";
+        let layout = layout_of(text);
+        let txn = parse_receipt(
+            "a.png",
+            text,
+            &[],
+            None,
+            DEFAULT_AMOUNT_CEILING,
+            DigitGrouping::Comma,
+            Some(layout),
+        );
+        let words = &txn.ocr_layout.as_ref().unwrap().words;
+        let texts = |indices: &[usize]| -> Vec<&str> {
+            indices.iter().map(|&i| words[i].text.as_str()).collect()
+        };
+        assert_eq!(txn.amount, 5000);
+        assert_eq!(texts(&txn.field_words.amount), ["5,000원"]);
+        assert_eq!(txn.field_words.amount, [8]);
+        assert_eq!(texts(&txn.field_words.merchant), ["5,000원", "마트"]);
+        assert_eq!(texts(&txn.field_words.datetime), ["2026.01.22", "16:35:39"]);
+    }
+
+    #[test]
+    fn krw_amount_decimal_and_period_grouping() {
+        assert_eq!(parse_krw_amount("45,000.00"), Ok(45000));
+        assert_eq!(parse_krw_amount("45,000.5"), Ok(45000));
+        assert_eq!(parse_krw_amount("45.000"), Ok(45000));
+        assert_eq!(parse_krw_amount("1.234.000"), Ok(1_234_000));
+        assert_eq!(
+            extract_first_amount("결제금액 1.234.000원").map(|(a, _)| a),
+            Ok(1_234_000)
+        );
+    }
+
+    #[test]
+    fn foreign_amount_needs_a_standalone_code_or_symbol() {
+        let read = |text: &str| extract_foreign_amount(text).map(|f| (f.currency, f.amount));
+        assert_eq!(read("현지승인금액 12.50 USD"), Some(("USD".into(), 12.5)));
+        assert_eq!(read("합계 $12.50"), Some(("USD".into(), 12.5)));
+        assert_eq!(read("€ 8.20"), Some(("EUR".into(), 8.2)));
+        assert_eq!(read("USDT 5"), None);
+        assert_eq!(read("주문번호 BUSD12"), None);
+    }
+
+    #[test]
+    fn shared_label_does_not_outweigh_screen_title() {
+        let text = "상세 이용내역\n스타벅스 강남점\n거래일시 2026.01.22 16:35\n45,000원";
+        assert_eq!(detect_format(text), CardFormat::CardAppScreenshot);
+        assert_eq!(
+            detect_format("거래일시 2026.01.22 16:35:39\n승인금액 27,600 원"),
+            CardFormat::HanaCard
+        );
+    }
+
+    #[test]
+    fn amount_currency_prefixes_and_suffixes() {
+        for (value, want) in [
+            ("₩45,000", 45000),
+            ("￦1,000", 1000),
+            ("45,000원정", 45000),
+            ("KRW 45,000", 45000),
+            ("45,000 원", 45000),
+        ] {
+            assert_eq!(hana(value).amount, want, "{}", value);
+            let t = parse(&format!(
+                "가게이름\n{}\n거래 일자 26. 1. 31 · 14:59:27\n",
+                value
+            ));
+            assert_eq!(t.amount, want, "{}", value);
+            assert_eq!(t.merchant, "가게이름", "{}", value);
+        }
+    }
+
+    #[test]
+    fn hana_merchant_wrapped_to_the_next_line() {
+        let t = parse(
+            "하나카드\n거래일시 2026.01.22 16:35:39\n가맹점명 네이버파이낸셜(주) /\n스마트스토어\n가맹점번호 12345\n승인금액 27,600 원\n",
+        );
+        assert_eq!(t.merchant, "네이버파이낸셜(주) / 스마트스토어");
+        // The next label and the amount are not swallowed
+        let t = parse(
+            "하나카드\n거래일시 2026.01.22 16:35:39\n가맹점명\n스타벅스\n승인금액 27,600 원\n",
+        );
+        assert_eq!(t.merchant, "스타벅스");
+        assert_eq!(t.amount, 27600);
+    }
+
+    #[test]
+    fn naver_amount_skips_the_points_line() {
+        // Points line before the merchant, then after the amount
+        for text in [
+            "결제 정보\n포인트 적립 435원\n해진구도일주유소일산지점\n43,489원\n거래 일자 26. 1. 31 · 14:59:27\n",
+            "결제 정보\n해진구도일주유소일산지점\n43,489원\n포인트 적립 435원\n거래 일자 26. 1. 31 · 14:59:27\n",
+        ] {
+            let t = parse(text);
+            assert_eq!(t.merchant, "해진구도일주유소일산지점");
+            assert_eq!(t.amount, 43489);
+        }
+    }
+
+    #[test]
+    fn korean_unit_amounts() {
+        assert_eq!(parse_korean_units("4만 5천원"), Some(45000));
+        assert_eq!(parse_korean_units("4만5천"), Some(45000));
+        assert_eq!(parse_korean_units("45만원"), Some(450000));
+        assert_eq!(parse_korean_units("3천500원"), Some(3500));
+        assert_eq!(parse_korean_units("1억"), Some(100000000));
+        assert_eq!(parse_korean_units("1억 2천만원"), Some(120000000));
+        assert_eq!(parse_korean_units("45000"), None);
+        assert_eq!(hana("4만 5천원").amount, 45000);
+        assert_eq!(crate::model::format_amount(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn naver_transaction_date_forms() {
+        let at = |y, m, d, h, min, s| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(h, min, s)
+                .unwrap()
+        };
+        for (date, want) in [
+            ("26. 1. 31 · 14:59:27", at(2026, 1, 31, 14, 59, 27)),
+            ("26.1.31 14:59", at(2026, 1, 31, 14, 59, 0)),
+            ("26 . 1 . 31-14:59:27", at(2026, 1, 31, 14, 59, 27)),
+            ("26. 1. 5 9:05", at(2026, 1, 5, 9, 5, 0)),
+        ] {
+            let t = parse(&format!(
+                "현대카드\n결제 정보\n스타벅스\n4,500원\n거래 일자 {}\n",
+                date
+            ));
+            assert_eq!(t.datetime, want, "{}", date);
+        }
+    }
+
+    #[test]
+    fn hana_cancellation_is_a_refund() {
+        let t = parse(
+            "하나카드\n거래일시 2026.01.23 10:11:12\n거래구분 취소\n승인금액 27,600 원\n취소금액 27,600 원\n가맹점명 스타벅스\n",
+        );
+        assert_eq!(t.amount, -27600);
+        assert_eq!(t.merchant, "스타벅스");
+        assert!(t.is_cancelled());
+
+        let t = parse(
+            "하나카드\n거래일시 2026.01.23 10:11:12\n거래구분 일시불\n승인금액 27,600 원\n가맹점명 스타벅스\n",
+        );
+        assert_eq!(t.amount, 27600);
+        assert!(!t.is_cancelled());
+    }
+
+    #[test]
+    fn merchant_split_syllables_are_merged() {
+        assert_eq!(clean_merchant_noise("스 타 벅 스", &[]), "스타벅스");
+        assert_eq!(
+            clean_merchant_noise("스 타 벅 스 강남점", &[]),
+            "스타벅스 강남점"
+        );
+        assert_eq!(
+            clean_merchant_noise("GS 2 5 편 의 점", &[]),
+            "GS 2 5 편의점"
+        );
+        // Real word boundaries stay
+        assert_eq!(clean_merchant_noise("교보 문고", &[]), "교보 문고");
+        assert_eq!(clean_merchant_noise("한 솥 도시락", &[]), "한 솥 도시락");
+    }
+
+    #[test]
+    fn flexible_datetime_matrix() {
+        for (text, want) in [
+            ("2026.01.22 16:35:39", Some("2026-01-22 16:35:39")),
+            ("2026.01.22 16:35", Some("2026-01-22 16:35:00")),
+            ("2026-1-2", Some("2026-01-02 00:00:00")),
+            ("2026/01/22 09:05", Some("2026-01-22 09:05:00")),
+            ("2026 01 22 16:35:39", Some("2026-01-22 16:35:39")),
+            ("2026년 1월 22일 16:35", Some("2026-01-22 16:35:00")),
+            ("2026. 01. 22.", Some("2026-01-22 00:00:00")),
+            ("26. 1. 31 · 14:59:27", Some("2026-01-31 14:59:27")),
+            ("26. 3. 9 ㆍ 22:39:54", Some("2026-03-09 22:39:54")),
+            ("26/01/31", Some("2026-01-31 00:00:00")),
+            ("거래일시2026.01.22 16:35:39", Some("2026-01-22 16:35:39")),
+            // An impossible date is skipped for the next one
+            ("2026.13.40 then 2026.02.03", Some("2026-02-03 00:00:00")),
+            ("합계 43,489원", None),
+            ("", None),
+        ] {
+            let got = extract_datetime_flexible(text).map(|(d, _)| d.to_string());
+            assert_eq!(got.as_deref(), want, "{}", text);
+        }
+    }
+
+    #[test]
+    fn business_number_extraction_and_check_digit() {
+        assert!(is_valid_business_number("220-81-62517"));
+        assert!(!is_valid_business_number("220-81-62518"));
+        let t = parse(
+            "하나카드\n거래일시 2026.01.22 16:35:39\n승인금액 45,000원\n가맹점명 X가게\n사업자번호: 220-81-62517\n",
+        );
+        assert_eq!(t.business_number.as_deref(), Some("220-81-62517"));
+        // OCR spacing around the dashes is normalized
+        let t = parse("가맹점 X\n123 - 45 - 67890\n1,000원\n");
+        assert_eq!(t.business_number.as_deref(), Some("123-45-67890"));
+        // Phone numbers and dates aren't business numbers
+        let t = parse("X가게\n1,000원\n2026-01-22\n010-1234-5678\n");
+        assert_eq!(t.business_number, None);
+    }
+}
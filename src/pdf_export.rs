@@ -5,12 +5,16 @@
  */
 
 //! Minimal PDF generator for receipts.
-//! One image per A4 page with an ASCII footer line.
+//! One receipt per A4 page: full-page image with an ASCII footer line,
+//! or a large summary header above a smaller image.
 //! No external PDF library — pure PDF syntax written as raw bytes.
 
+use std::borrow::Cow;
 use std::io::Write;
 
-use crate::model::CardTransaction;
+use crate::model::{
+    CardTransaction, DigitGrouping, FailedImage, format_amount_grouped, unset_datetime,
+};
 
 /// A4 page size in PDF points (1 pt = 1/72 inch)
 const A4_W: f64 = 595.276;
@@ -20,19 +24,76 @@ const MARGIN: f64 = 28.35;
 /// Footer area height in points (~15 mm)
 const FOOTER_H: f64 = 42.52;
 
-/// Generate a PDF byte stream with one receipt image per A4 page.
+/// Page layout for `generate_receipts_pdf`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PdfLayout {
+    /// Receipt image fills the page, small footer line
+    OnePerPage,
+    /// Large date/amount/merchant header above a smaller image
+    Summary,
+}
+
+/// Generate a PDF byte stream with one receipt per A4 page.
 ///
-/// Each page contains:
+/// With `PdfLayout::OnePerPage` each page contains:
 /// - The receipt image scaled to fill the available area (aspect-ratio preserved, centred)
 /// - An ASCII footer: `{index}. {datetime}  {amount}  {expense_type}`
 ///
+/// `PdfLayout::Summary` puts a large header (merchant romanized) above a smaller image.
+///
+/// A transaction's `extra_images` follow its page as full-image pages numbered
+/// under the same index (`3a`, `3b`, ...).
+///
 /// Uses the PDF built-in Helvetica font; only ASCII characters appear in the footer.
-pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>, String> {
-    if transactions.is_empty() {
+/// Each page also gets a bookmark (`{index}. {merchant}`, Korean kept) in the outline.
+///
+/// `failed` images (OCR could not read them) are appended after the receipts as
+/// full-image pages with an "OCR FAILED - manual entry required" footer.
+///
+/// Rows without image bytes (imported from CSV) get a text-only "NO IMAGE" page
+/// with the same footer, so page numbers still match the CSV rows.
+///
+/// With `include_filename` every page also names its source image: a romanized
+/// `File:` line under the footer, plus a text annotation holding the exact
+/// (possibly Korean) filename, so a page can be traced back to its screenshot.
+///
+/// `on_progress(done, total)` is called after each page is written, so callers can
+/// report progress for large batches (re-encoding non-JPEG images dominates the cost).
+pub fn generate_receipts_pdf(
+    transactions: &[&CardTransaction],
+    failed: &[&FailedImage],
+    layout: PdfLayout,
+    grouping: DigitGrouping,
+    include_filename: bool,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<u8>, String> {
+    if transactions.is_empty() && failed.is_empty() {
         return Err("No transactions to include in PDF".into());
     }
 
-    let n = transactions.len();
+    let mut pages: Vec<PageSource> = Vec::new();
+    for (number, &txn) in transactions.iter().enumerate() {
+        pages.push(PageSource::Receipt {
+            number,
+            txn,
+            extra: None,
+        });
+        pages.extend((0..txn.extra_images.len()).map(|k| PageSource::Receipt {
+            number,
+            txn,
+            extra: Some(k),
+        }));
+    }
+    pages.extend(
+        failed
+            .iter()
+            .enumerate()
+            .map(|(k, &image)| PageSource::Failed {
+                number: transactions.len() + k,
+                image,
+            }),
+    );
+    let n = pages.len();
 
     // PDF object layout (1-indexed):
     //   1        – Catalog
@@ -42,7 +103,12 @@ pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>
     //     4+3*i  – Page dictionary
     //     5+3*i  – Page content stream
     //     6+3*i  – Image XObject
-    let total_objs = 3 + 3 * n;
+    //   4+3*n    – Document information dictionary
+    //   5+3*n    – Outline (bookmark) root
+    //   6+3*n+i  – Bookmark for page i
+    let info_id = 4 + 3 * n;
+    let outlines_id = info_id + 1;
+    let total_objs = outlines_id + n;
 
     let mut buf: Vec<u8> = Vec::with_capacity(512 * 1024);
     let mut offsets = vec![0usize; total_objs + 1]; // 1-indexed; index 0 unused
@@ -57,7 +123,11 @@ pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>
 
     // ── Object 1: Catalog ───────────────────────────────────────────────────
     offsets[1] = buf.len();
-    w!("1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+    // Open with the bookmark sidebar so long bundles are easy to navigate
+    w!(
+        "1 0 obj\n<< /Type /Catalog /Pages 2 0 R /Outlines {} 0 R /PageMode /UseOutlines >>\nendobj\n",
+        outlines_id
+    );
 
     // ── Object 2: Pages tree ────────────────────────────────────────────────
     let kids: String = (0..n)
@@ -78,83 +148,92 @@ pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>
     );
 
     // ── Per-page objects ────────────────────────────────────────────────────
-    for (i, txn) in transactions.iter().enumerate() {
+    for (i, page) in pages.iter().enumerate() {
         let page_id = 4 + 3 * i;
         let content_id = 5 + 3 * i;
         let image_id = 6 + 3 * i;
+        let (txn, image_bytes, filename) = match *page {
+            PageSource::Receipt { txn, extra, .. } => (
+                Some(txn),
+                match extra {
+                    Some(k) => &txn.extra_images[k][..],
+                    None => &txn.image_bytes[..],
+                },
+                txn.filename.as_str(),
+            ),
+            PageSource::Failed { image, .. } => (None, &image.bytes[..], image.filename.as_str()),
+        };
+        let label = page.label();
 
-        // Load image and convert to RGB JPEG for PDF embedding
-        let img = image::load_from_memory(&txn.image_bytes)
-            .map_err(|e| format!("Receipt #{}: failed to load image — {e}", i + 1))?;
-        let rgb = img.into_rgb8();
-        let (img_w, img_h) = (rgb.width(), rgb.height());
-
-        let mut jpeg_buf: Vec<u8> = Vec::new();
-        image::DynamicImage::from(rgb)
-            .write_to(
-                &mut std::io::Cursor::new(&mut jpeg_buf),
-                image::ImageFormat::Jpeg,
-            )
-            .map_err(|e| format!("Receipt #{}: JPEG encode failed — {e}", i + 1))?;
-
-        // ── Image placement: centred, aspect-ratio preserved ────────────────
-        let avail_w = A4_W - 2.0 * MARGIN;
-        let avail_h = A4_H - FOOTER_H - 2.0 * MARGIN;
-        let aspect = img_w as f64 / img_h as f64;
-        let (draw_w, draw_h) = if aspect > avail_w / avail_h {
-            (avail_w, avail_w / aspect)
+        // Rows restored without their image (CSV import) get a text-only page,
+        // so one missing image doesn't abort the whole PDF
+        let embedded = if image_bytes.is_empty() {
+            None
         } else {
-            (avail_h * aspect, avail_h)
+            Some(embeddable_jpeg(i, image_bytes)?)
         };
-        let img_x = MARGIN + (avail_w - draw_w) / 2.0;
-        let img_y = FOOTER_H + MARGIN + (avail_h - draw_h) / 2.0;
-
-        // ── Footer text (ASCII only — Helvetica has no CJK glyphs) ──────────
-        let expense = txn.expense_type.as_deref().unwrap_or("-");
-        let expense_ascii: String = expense
-            .chars()
-            .map(|c| {
-                if c.is_ascii_graphic() || c == ' ' {
-                    c
-                } else {
-                    '?'
-                }
-            })
-            .collect();
-        let footer = format!(
-            "{}. {}  {}  {}",
-            i + 1,
-            txn.datetime.format("%Y-%m-%d %H:%M"),
-            fmt_amount(txn.amount),
-            expense_ascii,
-        );
 
-        // ── PDF content stream ───────────────────────────────────────────────
-        // Draw image: q ... cm /ImN Do Q
-        // Draw footer text: BT /F1 10 Tf x y Td (text) Tj ET
-        let content = format!(
-            "q\n{:.2} 0 0 {:.2} {:.2} {:.2} cm\n/Im{} Do\nQ\nBT\n/F1 10 Tf\n{:.2} {:.2} Td\n({}) Tj\nET\n",
-            draw_w,
-            draw_h,
-            img_x,
-            img_y,
-            image_id,
-            MARGIN,
-            FOOTER_H / 2.0 - 5.0,
-            pdf_str(&footer),
-        );
+        let file_line = format!("File: {}", romanize_hangul(filename));
+        // Failed-image footers already name the file
+        let note = (include_filename && txn.is_some()).then_some(file_line.as_str());
+
+        // Extra images always get the full page; the summary header is on the first one
+        let summary =
+            layout == PdfLayout::Summary && matches!(page, PageSource::Receipt { extra: None, .. });
+        let content = match (&embedded, txn) {
+            (None, Some(txn)) => missing_image_page(&receipt_footer(&label, txn, grouping), note),
+            (Some(jpeg), Some(txn)) if summary => {
+                summary_page(i, n, txn, grouping, note, jpeg.width, jpeg.height, image_id)
+            }
+            (Some(jpeg), Some(txn)) => full_image_page(
+                &receipt_footer(&label, txn, grouping),
+                note,
+                jpeg.width,
+                jpeg.height,
+                image_id,
+            ),
+            (_, None) => {
+                let footer = format!(
+                    "{}. OCR FAILED - manual entry required  ({})",
+                    label,
+                    romanize_hangul(filename),
+                );
+                match &embedded {
+                    Some(jpeg) => full_image_page(&footer, None, jpeg.width, jpeg.height, image_id),
+                    None => missing_image_page(&footer, None),
+                }
+            }
+        };
         let content_bytes = content.as_bytes();
 
         // ── Page dictionary ──────────────────────────────────────────────────
+        let xobjects = if embedded.is_some() {
+            format!(" /XObject << /Im{} {} 0 R >>", image_id, image_id)
+        } else {
+            String::new()
+        };
+        // Collapsed note icon at the bottom right; its text keeps Korean as-is
+        let annots = if include_filename {
+            format!(
+                " /Annots [<< /Type /Annot /Subtype /Text /Rect [{:.2} {:.2} {:.2} {:.2}] /Contents {} /Open false >>]",
+                A4_W - MARGIN - 18.0,
+                MARGIN / 2.0,
+                A4_W - MARGIN,
+                MARGIN / 2.0 + 18.0,
+                pdf_text_string(filename)
+            )
+        } else {
+            String::new()
+        };
         offsets[page_id] = buf.len();
         w!(
-            "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Contents {} 0 R /Resources << /Font << /F1 3 0 R >> /XObject << /Im{} {} 0 R >> >> >>\nendobj\n",
+            "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Contents {} 0 R /Resources << /Font << /F1 3 0 R >>{} >>{} >>\nendobj\n",
             page_id,
             A4_W,
             A4_H,
             content_id,
-            image_id,
-            image_id
+            xobjects,
+            annots
         );
 
         // ── Content stream ───────────────────────────────────────────────────
@@ -167,21 +246,100 @@ pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>
         buf.extend_from_slice(content_bytes);
         w!("\nendstream\nendobj\n");
 
-        // ── Image XObject (DCTDecode = JPEG) ─────────────────────────────────
+        // ── Image XObject (DCTDecode = JPEG); a null object keeps the numbering ──
         offsets[image_id] = buf.len();
+        match embedded {
+            Some(jpeg) => {
+                w!(
+                    "{} 0 obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                    image_id,
+                    jpeg.width,
+                    jpeg.height,
+                    jpeg.color_space,
+                    jpeg.data.len()
+                );
+                buf.extend_from_slice(&jpeg.data);
+                w!("\nendstream\nendobj\n");
+            }
+            None => w!("{} 0 obj\nnull\nendobj\n", image_id),
+        }
+
+        on_progress(i + 1, n);
+    }
+
+    // ── Document information dictionary ─────────────────────────────────────
+    // CreationDate comes from the earliest parsed transaction, not the export time
+    // (omitted when no row has a date that was read)
+    let creation_date = transactions
+        .iter()
+        .map(|t| t.datetime)
+        .filter(|d| *d != unset_datetime())
+        .min()
+        .map(|d| format!(" /CreationDate (D:{})", d.format("%Y%m%d%H%M%S")))
+        .unwrap_or_default();
+    offsets[info_id] = buf.len();
+    w!(
+        "{} 0 obj\n<< /Title {} /Producer (card-receipt-ocr {}){} >>\nendobj\n",
+        info_id,
+        pdf_text_string("카드 영수증 모음"),
+        env!("CARGO_PKG_VERSION"),
+        creation_date,
+    );
+
+    // ── Outline: one bookmark per page, "{index}. {merchant}" ────────────────
+    // Titles are UTF-16 text strings, so Korean names show as-is in the sidebar
+    let item_id = |i: usize| outlines_id + 1 + i;
+    offsets[outlines_id] = buf.len();
+    w!(
+        "{} 0 obj\n<< /Type /Outlines /First {} 0 R /Last {} 0 R /Count {} >>\nendobj\n",
+        outlines_id,
+        item_id(0),
+        item_id(n - 1),
+        n
+    );
+    for (i, page) in pages.iter().enumerate() {
+        let label = page.label();
+        let title = match *page {
+            PageSource::Receipt { txn, .. } if !txn.merchant.trim().is_empty() => {
+                format!("{}. {}", label, txn.merchant.trim())
+            }
+            PageSource::Receipt { txn, .. } => format!("{}. {}", label, txn.filename),
+            PageSource::Failed { image, .. } => {
+                format!("{}. 인식 실패: {}", label, image.filename)
+            }
+        };
+        let prev = if i > 0 {
+            format!(" /Prev {} 0 R", item_id(i - 1))
+        } else {
+            String::new()
+        };
+        let next = if i + 1 < n {
+            format!(" /Next {} 0 R", item_id(i + 1))
+        } else {
+            String::new()
+        };
+        offsets[item_id(i)] = buf.len();
         w!(
-            "{} 0 obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
-            image_id,
-            img_w,
-            img_h,
-            jpeg_buf.len()
+            "{} 0 obj\n<< /Title {} /Parent {} 0 R{}{} /Dest [{} 0 R /Fit] >>\nendobj\n",
+            item_id(i),
+            pdf_text_string(&title),
+            outlines_id,
+            prev,
+            next,
+            4 + 3 * i
         );
-        buf.extend_from_slice(&jpeg_buf);
-        w!("\nendstream\nendobj\n");
     }
 
     // ── Cross-reference table ────────────────────────────────────────────────
+    // Offset 0 is the header, so a zero entry means an object id was reserved in
+    // `total_objs` but never written; the xref would point viewers at garbage
+    if let Some(missing) = (1..=total_objs).find(|&id| offsets[id] == 0) {
+        return Err(format!("PDF object {missing} was never written"));
+    }
     // Each entry is exactly 20 bytes: 10-digit offset SP 5-digit gen SP [f|n] SP LF
+    if buf.len() as u64 > 9_999_999_999 {
+        return Err("PDF exceeds the 10-digit xref offset limit".into());
+    }
     let xref_pos = buf.len();
     w!("xref\n0 {}\n", total_objs + 1);
     w!("0000000000 65535 f \n"); // free object 0
@@ -190,25 +348,384 @@ pub fn generate_receipts_pdf(transactions: &[CardTransaction]) -> Result<Vec<u8>
     }
 
     // ── Trailer ──────────────────────────────────────────────────────────────
-    w!("trailer\n<< /Size {} /Root 1 0 R >>\n", total_objs + 1);
+    w!(
+        "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\n",
+        total_objs + 1,
+        info_id
+    );
     w!("startxref\n{}\n%%EOF\n", xref_pos);
 
     Ok(buf)
 }
 
-/// Format an amount with thousands separators: 45000 → "45,000"
-fn fmt_amount(amount: u64) -> String {
-    let s = amount.to_string();
-    let chars: Vec<char> = s.chars().collect();
-    let n = chars.len();
-    let mut result = String::new();
-    for (i, &c) in chars.iter().enumerate() {
-        if i > 0 && (n - i).is_multiple_of(3) {
-            result.push(',');
+/// What one PDF page shows. `number` is the 0-based receipt number in the
+/// footer, shared by a transaction's main and extra image pages.
+enum PageSource<'a> {
+    Receipt {
+        number: usize,
+        txn: &'a CardTransaction,
+        /// Index into `extra_images`; None for the main image
+        extra: Option<usize>,
+    },
+    Failed {
+        number: usize,
+        image: &'a FailedImage,
+    },
+}
+
+impl PageSource<'_> {
+    /// Page label: `3` for a main image, `3a`, `3b`, ... for its extra images
+    fn label(&self) -> String {
+        match *self {
+            PageSource::Receipt {
+                number,
+                extra: Some(k),
+                ..
+            } => match u8::try_from(k).ok().filter(|&k| k < 26) {
+                Some(k) => format!("{}{}", number + 1, char::from(b'a' + k)),
+                None => format!("{}-{}", number + 1, k + 1),
+            },
+            PageSource::Receipt { number, .. } | PageSource::Failed { number, .. } => {
+                (number + 1).to_string()
+            }
+        }
+    }
+}
+
+/// JPEG data ready for a DCTDecode image XObject
+struct EmbeddedJpeg<'a> {
+    data: Cow<'a, [u8]>,
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+}
+
+/// JPEGs are embedded as-is (no decode, no generation loss);
+/// anything else is converted to an RGB JPEG
+fn embeddable_jpeg(i: usize, image_bytes: &[u8]) -> Result<EmbeddedJpeg<'_>, String> {
+    if let Some(header) = jpeg_header(image_bytes) {
+        return Ok(EmbeddedJpeg {
+            data: Cow::Borrowed(image_bytes),
+            width: header.width,
+            height: header.height,
+            color_space: header.color_space,
+        });
+    }
+    let img = image::load_from_memory(image_bytes)
+        .map_err(|e| format!("Receipt #{}: failed to load image — {e}", i + 1))?;
+    let rgb = img.into_rgb8();
+    let (width, height) = (rgb.width(), rgb.height());
+
+    let mut jpeg_buf: Vec<u8> = Vec::new();
+    image::DynamicImage::from(rgb)
+        .write_to(
+            &mut std::io::Cursor::new(&mut jpeg_buf),
+            image::ImageFormat::Jpeg,
+        )
+        .map_err(|e| format!("Receipt #{}: JPEG encode failed — {e}", i + 1))?;
+    Ok(EmbeddedJpeg {
+        data: Cow::Owned(jpeg_buf),
+        width,
+        height,
+        color_space: "DeviceRGB",
+    })
+}
+
+/// Size and PDF colour space of a JPEG that can be embedded unchanged
+struct JpegHeader {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+}
+
+/// Read the frame header (SOFn) of an 8-bit grey or RGB JPEG. Returns `None` for
+/// non-JPEG data and for CMYK/12-bit JPEGs, which are safer to re-encode.
+fn jpeg_header(bytes: &[u8]) -> Option<JpegHeader> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut at = 2;
+    while at + 4 <= bytes.len() {
+        if bytes[at] != 0xFF {
+            return None;
+        }
+        let marker = bytes[at + 1];
+        // Fill bytes and standalone markers carry no length
+        if marker == 0xFF {
+            at += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            at += 2;
+            continue;
+        }
+        let len = usize::from(u16::from_be_bytes([bytes[at + 2], bytes[at + 3]]));
+        // SOF0–SOF15 except DHT (C4), JPG (C8) and DAC (CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let frame = bytes.get(at + 4..at + 2 + len)?;
+            let (precision, height, width, components) = (
+                *frame.first()?,
+                u16::from_be_bytes([*frame.get(1)?, *frame.get(2)?]),
+                u16::from_be_bytes([*frame.get(3)?, *frame.get(4)?]),
+                *frame.get(5)?,
+            );
+            let color_space = match components {
+                1 => "DeviceGray",
+                3 => "DeviceRGB",
+                _ => return None,
+            };
+            return (precision == 8 && width > 0 && height > 0).then_some(JpegHeader {
+                width: width.into(),
+                height: height.into(),
+                color_space,
+            });
+        }
+        // Image data starts without a frame header: not a usable JPEG
+        if marker == 0xDA || len < 2 {
+            return None;
+        }
+        at += 2 + len;
+    }
+    None
+}
+
+/// Content stream for `PdfLayout::OnePerPage` (and failed-image pages):
+/// the image fills the page, with an ASCII footer (and optional note line)
+fn full_image_page(
+    footer: &str,
+    note: Option<&str>,
+    img_w: u32,
+    img_h: u32,
+    image_id: usize,
+) -> String {
+    // ── Image placement: centred, aspect-ratio preserved ────────────────
+    let avail_w = A4_W - 2.0 * MARGIN;
+    let avail_h = A4_H - FOOTER_H - 2.0 * MARGIN;
+    let aspect = img_w as f64 / img_h as f64;
+    let (draw_w, draw_h) = if aspect > avail_w / avail_h {
+        (avail_w, avail_w / aspect)
+    } else {
+        (avail_h * aspect, avail_h)
+    };
+    let img_x = MARGIN + (avail_w - draw_w) / 2.0;
+    let img_y = FOOTER_H + MARGIN + (avail_h - draw_h) / 2.0;
+
+    // ── PDF content stream ───────────────────────────────────────────────
+    // Draw image: q ... cm /ImN Do Q, then the footer text
+    format!(
+        "q\n{:.2} 0 0 {:.2} {:.2} {:.2} cm\n/Im{} Do\nQ\n{}",
+        draw_w,
+        draw_h,
+        img_x,
+        img_y,
+        image_id,
+        footer_text(footer, note),
+    )
+}
+
+/// Footer text block: BT /F1 10 Tf x y Td (text) Tj ET. A note goes on a
+/// smaller second line, with the footer moved up to make room.
+fn footer_text(footer: &str, note: Option<&str>) -> String {
+    let y = FOOTER_H / 2.0 - 5.0;
+    match note {
+        None => format!(
+            "BT\n/F1 10 Tf\n0 g\n{:.2} {:.2} Td\n({}) Tj\nET\n",
+            MARGIN,
+            y,
+            pdf_str(footer)
+        ),
+        Some(note) => format!(
+            "BT\n/F1 10 Tf\n0 g\n{:.2} {:.2} Td\n({}) Tj\n/F1 8 Tf\n0 -11 Td\n({}) Tj\nET\n",
+            MARGIN,
+            y + 6.0,
+            pdf_str(footer),
+            pdf_str(note)
+        ),
+    }
+}
+
+/// Content stream for a row without an image (e.g. imported from CSV):
+/// a centred "NO IMAGE" notice above the usual footer
+fn missing_image_page(footer: &str, note: Option<&str>) -> String {
+    let notice = "NO IMAGE";
+    let size = 28.0;
+    // Helvetica capitals average ~0.67 em
+    let x = (A4_W - notice.len() as f64 * size * 0.67) / 2.0;
+    format!(
+        "BT\n/F1 {:.1} Tf\n0.6 g\n{:.2} {:.2} Td\n({}) Tj\nET\n{}",
+        size,
+        x,
+        A4_H / 2.0,
+        notice,
+        footer_text(footer, note),
+    )
+}
+
+/// Footer line for a receipt page (ASCII only — Helvetica has no CJK glyphs)
+fn receipt_footer(label: &str, txn: &CardTransaction, grouping: DigitGrouping) -> String {
+    let expense = txn.expense_type.as_deref().unwrap_or("-");
+    let expense_ascii: String = expense
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect();
+    format!(
+        "{}. {}  {}  {}",
+        label,
+        txn.datetime.format("%Y-%m-%d %H:%M"),
+        format_amount_grouped(txn.amount, grouping),
+        expense_ascii,
+    )
+}
+
+/// Content stream for `PdfLayout::Summary`: large date/amount/merchant header
+/// above a smaller centred image, for small receipts. A note (source filename)
+/// goes bottom left, opposite the page number.
+#[allow(clippy::too_many_arguments)]
+fn summary_page(
+    i: usize,
+    n: usize,
+    txn: &CardTransaction,
+    grouping: DigitGrouping,
+    note: Option<&str>,
+    img_w: u32,
+    img_h: u32,
+    image_id: usize,
+) -> String {
+    let header_h = 150.0;
+    let top = A4_H - MARGIN;
+
+    // ── Header lines: (font size, text) ─────────────────────────────────────
+    let merchant = romanize_hangul(&txn.merchant);
+    let lines = [
+        (22.0, txn.datetime.format("%Y-%m-%d %H:%M").to_string()),
+        (
+            28.0,
+            format!("{} KRW", format_amount_grouped(txn.amount, grouping)),
+        ),
+        (
+            16.0,
+            if merchant.is_empty() {
+                "-".into()
+            } else {
+                merchant
+            },
+        ),
+        (
+            12.0,
+            txn.expense_type
+                .as_deref()
+                .map(romanize_hangul)
+                .unwrap_or_else(|| "-".into()),
+        ),
+    ];
+    let mut content = String::from("BT\n");
+    let mut y = top;
+    for (size, text) in &lines {
+        y -= size * 1.35;
+        content.push_str(&format!(
+            "/F1 {:.0} Tf\n1 0 0 1 {:.2} {:.2} Tm\n({}) Tj\n",
+            size,
+            MARGIN,
+            y,
+            pdf_str(text)
+        ));
+    }
+    if let Some(note) = note {
+        content.push_str(&format!(
+            "/F1 9 Tf\n1 0 0 1 {:.2} {:.2} Tm\n({}) Tj\n",
+            MARGIN,
+            MARGIN,
+            pdf_str(note)
+        ));
+    }
+    // Page number, bottom right
+    content.push_str(&format!(
+        "/F1 9 Tf\n1 0 0 1 {:.2} {:.2} Tm\n({} / {}) Tj\nET\n",
+        A4_W - MARGIN - 30.0,
+        MARGIN,
+        i + 1,
+        n
+    ));
+    content.push_str(&format!(
+        "{:.2} w\n{:.2} {:.2} m {:.2} {:.2} l S\n",
+        0.5,
+        MARGIN,
+        top - header_h,
+        A4_W - MARGIN,
+        top - header_h
+    ));
+
+    // ── Image: at most ~60% of the width, centred below the header ─────────
+    let avail_w = (A4_W - 2.0 * MARGIN) * 0.6;
+    let avail_h = top - header_h - 2.0 * MARGIN;
+    let aspect = img_w as f64 / img_h as f64;
+    let (draw_w, draw_h) = if aspect > avail_w / avail_h {
+        (avail_w, avail_w / aspect)
+    } else {
+        (avail_h * aspect, avail_h)
+    };
+    let img_x = (A4_W - draw_w) / 2.0;
+    let img_y = MARGIN + (avail_h - draw_h) / 2.0 + MARGIN;
+    content.push_str(&format!(
+        "q\n{:.2} 0 0 {:.2} {:.2} {:.2} cm\n/Im{} Do\nQ\n",
+        draw_w, draw_h, img_x, img_y, image_id
+    ));
+    content
+}
+
+/// Revised Romanization of Hangul syllables (letter-by-letter, no sound-change
+/// rules) so Korean merchant names can be shown with Helvetica.
+/// ASCII passes through; other characters are dropped.
+fn romanize_hangul(s: &str) -> String {
+    const INITIAL: [&str; 19] = [
+        "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t",
+        "p", "h",
+    ];
+    const MEDIAL: [&str; 21] = [
+        "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo",
+        "we", "wi", "yu", "eu", "ui", "i",
+    ];
+    const FINAL: [&str; 28] = [
+        "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p",
+        "p", "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+    ];
+
+    let mut out = String::new();
+    let mut word_start = true;
+    for c in s.chars() {
+        let code = c as u32;
+        if (0xAC00..=0xD7A3).contains(&code) {
+            let idx = (code - 0xAC00) as usize;
+            let syllable = format!(
+                "{}{}{}",
+                INITIAL[idx / (21 * 28)],
+                MEDIAL[(idx / 28) % 21],
+                FINAL[idx % 28]
+            );
+            if word_start {
+                let mut chars = syllable.chars();
+                if let Some(first) = chars.next() {
+                    out.push(first.to_ascii_uppercase());
+                    out.extend(chars);
+                }
+            } else {
+                out.push_str(&syllable);
+            }
+            word_start = false;
+        } else if c.is_ascii() {
+            out.push(c);
+            word_start = !c.is_ascii_alphanumeric();
+        } else {
+            word_start = true;
         }
-        result.push(c);
     }
-    result
+    out.trim().to_string()
 }
 
 /// Escape special characters for a PDF literal string `(...)`.
@@ -225,3 +742,124 @@ fn pdf_str(s: &str) -> String {
     }
     out
 }
+
+/// Encode a PDF text string as UTF-16BE hex with BOM (`<FEFF...>`), so
+/// non-ASCII metadata such as Korean titles survives in viewers.
+fn pdf_text_string(s: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in s.encode_utf16() {
+        out.push_str(&format!("{:04X}", unit));
+    }
+    out.push('>');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use std::sync::Arc;
+
+    fn png(width: u32, height: u32) -> Arc<[u8]> {
+        let mut bytes = Vec::new();
+        image::DynamicImage::from(image::RgbImage::new(width, height))
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        bytes.into()
+    }
+
+    #[test]
+    fn every_xref_entry_points_at_its_object() {
+        let mut txn = parser::parse_receipt(
+            "a.png",
+            "",
+            &[],
+            None,
+            parser::DEFAULT_AMOUNT_CEILING,
+            DigitGrouping::Comma,
+            None,
+        );
+        txn.image_bytes = png(4, 3);
+        txn.extra_images = vec![png(2, 2)];
+        let failed = FailedImage {
+            filename: "b.png".into(),
+            bytes: png(3, 3).to_vec(),
+            reason: "OCR 실패".into(),
+        };
+        let pdf = generate_receipts_pdf(
+            &[&txn],
+            &[&failed],
+            PdfLayout::OnePerPage,
+            DigitGrouping::Comma,
+            true,
+            |_, _| {},
+        )
+        .unwrap();
+
+        let text = String::from_utf8_lossy(&pdf);
+        let xref_pos: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let mut lines = pdf[xref_pos..].split(|&b| b == b'\n').skip(1);
+        let count: usize = std::str::from_utf8(lines.next().unwrap())
+            .unwrap()
+            .trim_start_matches("0 ")
+            .parse()
+            .unwrap();
+        // Free object 0, five fixed objects, then 4 per page (3 objects + bookmark) for 3 pages
+        assert_eq!(count, 1 + 5 + 4 * 3);
+        for (id, entry) in lines.skip(1).take(count - 1).enumerate() {
+            let id = id + 1;
+            let offset: usize = std::str::from_utf8(&entry[..10]).unwrap().parse().unwrap();
+            let header = format!("{} 0 obj", id);
+            assert!(
+                pdf[offset..].starts_with(header.as_bytes()),
+                "object {}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn creation_date_ignores_unread_dates() {
+        let pdf_text = |transactions: &[&CardTransaction]| {
+            let pdf = generate_receipts_pdf(
+                transactions,
+                &[],
+                PdfLayout::OnePerPage,
+                DigitGrouping::Comma,
+                false,
+                |_, _| {},
+            )
+            .unwrap();
+            String::from_utf8_lossy(&pdf).into_owned()
+        };
+        let unread = parser::parse_receipt(
+            "a.png",
+            "",
+            &[],
+            None,
+            parser::DEFAULT_AMOUNT_CEILING,
+            DigitGrouping::Comma,
+            None,
+        );
+        assert_eq!(unread.datetime, unset_datetime());
+        assert!(!pdf_text(&[&unread]).contains("/CreationDate"));
+
+        let mut read = unread.clone();
+        read.datetime = chrono::NaiveDate::from_ymd_opt(2026, 1, 22)
+            .unwrap()
+            .and_hms_opt(16, 35, 39)
+            .unwrap();
+        assert!(pdf_text(&[&unread, &read]).contains("/CreationDate (D:20260122163539)"));
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ * SPDX-FileCopyrightText: © 2025 Jinwoo Park (pmnxis@gmail.com)
+ *
+ * SPDX-License-Identifier: MIT
+ */
+
+//! Manual image adjustments applied before OCR, for screenshots that
+//! Tesseract reads poorly as-is (low contrast, dark themes, faded paper),
+//! and the EXIF capture time read from the upload.
+
+use chrono::NaiveDateTime;
+use image::DynamicImage;
+
+/// Threshold used when binarization is first turned on
+pub const DEFAULT_THRESHOLD: u8 = 128;
+
+/// Per-image brightness/contrast/threshold chosen in the pending view
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImageAdjust {
+    /// Added to every channel (-100..=100)
+    pub brightness: i32,
+    /// Contrast change in percent (-50..=100)
+    pub contrast: f32,
+    /// Binarize to black/white at this luminance, if set
+    pub threshold: Option<u8>,
+}
+
+impl ImageAdjust {
+    /// True when the image is used as uploaded
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Apply the adjustments in order: brightness, contrast, then threshold
+pub fn adjust_image(img: DynamicImage, adjust: &ImageAdjust) -> DynamicImage {
+    if adjust.is_identity() {
+        return img;
+    }
+    let mut img = img;
+    if adjust.brightness != 0 {
+        img = img.brighten(adjust.brightness);
+    }
+    if adjust.contrast != 0.0 {
+        img = img.adjust_contrast(adjust.contrast);
+    }
+    if let Some(threshold) = adjust.threshold {
+        let mut luma = img.to_luma8();
+        for p in luma.pixels_mut() {
+            p.0[0] = if p.0[0] >= threshold { 255 } else { 0 };
+        }
+        img = DynamicImage::ImageLuma8(luma);
+    }
+    img
+}
+
+/// Adjusted copy of an encoded image, as PNG for OCR
+pub fn adjust_bytes(bytes: &[u8], adjust: &ImageAdjust) -> Result<Vec<u8>, String> {
+    let img = image::load_from_memory(bytes).map_err(|e| format!("이미지 디코딩 실패: {}", e))?;
+    let mut out = Vec::new();
+    adjust_image(img, adjust)
+        .write_to(&mut std::io::Cursor::new(&mut out), image::ImageFormat::Png)
+        .map_err(|e| format!("이미지 인코딩 실패: {}", e))?;
+    Ok(out)
+}
+
+/// Capture time (`DateTimeOriginal`, else `DateTime`) from the EXIF block of a
+/// JPEG (APP1 segment) or PNG (`eXIf` chunk). Screenshots usually have none.
+pub fn exif_datetime(bytes: &[u8]) -> Option<NaiveDateTime> {
+    let tiff = exif_tiff_block(bytes)?;
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |at: usize| -> Option<u16> {
+        let b: [u8; 2] = tiff.get(at..at.checked_add(2)?)?.try_into().ok()?;
+        Some(if big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    };
+    let u32_at = |at: usize| -> Option<usize> {
+        let b: [u8; 4] = tiff.get(at..at.checked_add(4)?)?.try_into().ok()?;
+        let v = if big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        };
+        usize::try_from(v).ok()
+    };
+    // Value offset of `tag` in the IFD at `ifd` (12-byte entries after a count).
+    // Offsets come from the file, so sums are checked: usize is 32 bits on wasm32.
+    let find_tag = |ifd: usize, tag: u16| -> Option<usize> {
+        let count = usize::from(u16_at(ifd)?);
+        let first = ifd.checked_add(2)?;
+        (0..count)
+            .map_while(|i| first.checked_add(i.checked_mul(12)?))
+            .find(|&entry| u16_at(entry) == Some(tag))
+            .and_then(|entry| u32_at(entry.checked_add(8)?))
+    };
+    let read_datetime = |at: usize| -> Option<NaiveDateTime> {
+        let raw = std::str::from_utf8(tiff.get(at..at.checked_add(19)?)?).ok()?;
+        NaiveDateTime::parse_from_str(raw, "%Y:%m:%d %H:%M:%S").ok()
+    };
+
+    let ifd0 = u32_at(4)?;
+    let original = find_tag(ifd0, 0x8769)
+        .and_then(|exif_ifd| find_tag(exif_ifd, 0x9003))
+        .and_then(read_datetime);
+    original.or_else(|| find_tag(ifd0, 0x0132).and_then(read_datetime))
+}
+
+/// TIFF-structured EXIF data inside a JPEG or PNG file
+fn exif_tiff_block(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        let mut at = 2;
+        while at + 4 <= bytes.len() && bytes[at] == 0xFF {
+            let marker = bytes[at + 1];
+            let len = usize::from(u16::from_be_bytes([bytes[at + 2], bytes[at + 3]]));
+            // Metadata segments come before the image data
+            if marker == 0xDA || len < 2 {
+                return None;
+            }
+            let segment = bytes.get(at + 4..at.checked_add(2 + len)?)?;
+            if marker == 0xE1
+                && let Some(tiff) = segment.strip_prefix(b"Exif\0\0")
+            {
+                return Some(tiff);
+            }
+            at = at.checked_add(2 + len)?;
+        }
+        None
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let mut at = 8;
+        while at + 8 <= bytes.len() {
+            let len =
+                usize::try_from(u32::from_be_bytes(bytes[at..at + 4].try_into().ok()?)).ok()?;
+            let kind = &bytes[at + 4..at + 8];
+            let data = bytes.get(at + 8..(at + 8).checked_add(len)?)?;
+            if kind == b"eXIf" {
+                return Some(data);
+            }
+            if kind == b"IDAT" || kind == b"IEND" {
+                return None;
+            }
+            at = (at + 12).checked_add(len)?;
+        }
+        None
+    } else {
+        None
+    }
+}
@@ -65,13 +65,17 @@ pub fn download_csv(filename: &str, csv_content: &str) -> Result<(), String> {
 
 /// Bundle images (numbered), CSV, and PDF into a single ZIP archive and trigger download.
 ///
-/// - Images are renamed to their 1-based index with the original extension (`1.jpg`, `2.png`, …)
+/// - Images are renamed to their 1-based index with the original extension (`1.jpg`, `2.png`, …),
+///   or keep their original filenames when `keep_original_names` is set (`a_2.jpg` on collision)
 /// - CSV is stored as `카드사용내역.csv`
 /// - PDF is stored as `영수증모음.pdf`
+/// - Optional per-category CSVs are stored as `by_category/{label}.csv`
 pub fn download_receipt_bundle(
     images: &[(&str, &[u8])], // (original_filename, bytes)
     csv_bytes: &[u8],
     pdf_bytes: &[u8],
+    category_csvs: &[(String, String)], // (expense label, csv content)
+    keep_original_names: bool,
     zip_filename: &str,
 ) -> Result<(), String> {
     use std::io::Write;
@@ -87,17 +91,27 @@ pub fn download_receipt_bundle(
         let cursor = std::io::Cursor::new(&mut buf);
         let mut zip = ZipWriter::new(cursor);
 
-        // Numbered receipt images
+        // Receipt images: numbered (matches CSV/PDF order) or original names
+        let mut used_names = std::collections::HashSet::new();
         for (i, (original_name, bytes)) in images.iter().enumerate() {
             if bytes.is_empty() {
                 continue;
             }
-            let ext = std::path::Path::new(original_name)
+            let path = std::path::Path::new(original_name);
+            let ext = path
                 .extension()
                 .and_then(|e| e.to_str())
                 .unwrap_or("jpg")
                 .to_ascii_lowercase();
-            let entry_name = format!("{}.{}", i + 1, ext);
+            let entry_name = if keep_original_names {
+                let stem = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("receipt");
+                unique_entry_name(&sanitize_entry_name(stem), &ext, &mut used_names)
+            } else {
+                format!("{}.{}", i + 1, ext)
+            };
             zip.start_file(&entry_name, store)
                 .map_err(|e| format!("ZIP: start_file error: {e}"))?;
             zip.write_all(bytes)
@@ -112,6 +126,15 @@ pub fn download_receipt_bundle(
                 .map_err(|e| format!("ZIP: CSV write error: {e}"))?;
         }
 
+        // Per-category CSVs
+        for (label, csv) in category_csvs {
+            let entry_name = format!("by_category/{}.csv", sanitize_entry_name(label));
+            zip.start_file(&entry_name, deflate)
+                .map_err(|e| format!("ZIP: category CSV start_file error: {e}"))?;
+            zip.write_all(csv.as_bytes())
+                .map_err(|e| format!("ZIP: category CSV write error: {e}"))?;
+        }
+
         // PDF
         if !pdf_bytes.is_empty() {
             zip.start_file("영수증모음.pdf", deflate)
@@ -126,3 +149,29 @@ pub fn download_receipt_bundle(
 
     download_file(zip_filename, &buf, "application/zip")
 }
+
+/// `stem.ext`, or `stem_2.ext`, `stem_3.ext`, … if the name is already taken
+/// (compared case-insensitively, as on Windows/macOS file systems)
+fn unique_entry_name(
+    stem: &str,
+    ext: &str,
+    used: &mut std::collections::HashSet<String>,
+) -> String {
+    let mut name = format!("{}.{}", stem, ext);
+    let mut n = 2;
+    while !used.insert(name.to_lowercase()) {
+        name = format!("{}_{}.{}", stem, n, ext);
+        n += 1;
+    }
+    name
+}
+
+/// Replace characters that are path separators or invalid in common file systems
+fn sanitize_entry_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}